@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Addresses and values are this many bits wide.
+pub const WIDTH: usize = 36;
+
+/// A bitmask like `XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Mask {
+    pub ones: u64,
+    pub floating: u64,
+}
+
+impl FromStr for Mask {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != WIDTH {
+            return Err(format!("Mask {} isn't {} bits", s, WIDTH));
+        }
+        let mut mask = Mask::default();
+        for (i, c) in s.bytes().rev().enumerate() {
+            match c {
+                b'1' => mask.ones |= 1 << i,
+                b'X' => mask.floating |= 1 << i,
+                b'0' => (),
+                _ => return Err(format!("Bad mask bit {}", c as char)),
+            }
+        }
+        Ok(mask)
+    }
+}
+
+impl Mask {
+    /// Version 1: `1`s and `0`s overwrite the value, `X`s leave it alone.
+    pub fn value(&self, value: u64) -> u64 {
+        self.ones | (value & self.floating)
+    }
+
+    /// Version 2: `1`s overwrite the address, `0`s leave it alone and `X`s
+    /// take every value.
+    pub fn address(&self, address: u64) -> Ternary {
+        Ternary::new(address | self.ones, self.floating)
+    }
+}
+
+/// A set of addresses where some bits are fixed and the rest take every
+/// value. Bits that float are always zero in `fixed`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Ternary {
+    pub fixed: u64,
+    pub floating: u64,
+}
+
+impl Ternary {
+    pub fn new(fixed: u64, floating: u64) -> Self {
+        Self {
+            fixed: fixed & !floating,
+            floating,
+        }
+    }
+
+    pub fn size(&self) -> u64 {
+        1 << self.floating.count_ones()
+    }
+
+    pub fn contains(&self, address: u64) -> bool {
+        address & !self.floating == self.fixed
+    }
+
+    /// The addresses in both sets, if there are any.
+    pub fn intersect(&self, other: &Ternary) -> Option<Ternary> {
+        let both_fixed = !self.floating & !other.floating;
+        if (self.fixed ^ other.fixed) & both_fixed != 0 {
+            return None;
+        }
+        Some(Ternary::new(
+            self.fixed | other.fixed,
+            self.floating & other.floating,
+        ))
+    }
+
+    /// The addresses in this set but not `other`, as disjoint sets.
+    ///
+    /// Each bit that floats here but is fixed in `other` splits off the half
+    /// with that bit set the other way; what's left after every split is
+    /// exactly the overlap.
+    pub fn subtract(&self, other: &Ternary) -> Vec<Ternary> {
+        if self.intersect(other).is_none() {
+            return vec![*self];
+        }
+        let mut pieces = vec![];
+        let mut rest = *self;
+        let mut splits = self.floating & !other.floating;
+        while splits != 0 {
+            let bit = splits & splits.wrapping_neg();
+            splits &= !bit;
+            let floating = rest.floating & !bit;
+            pieces.push(Ternary::new(rest.fixed | (!other.fixed & bit), floating));
+            rest = Ternary::new(rest.fixed | (other.fixed & bit), floating);
+        }
+        pieces
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Instruction {
+    Mask(Mask),
+    Write { address: u64, value: u64 },
+}
+
+impl FromStr for Instruction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (left, right) = s
+            .split_once(" = ")
+            .ok_or_else(|| format!("Bad instruction {}", s))?;
+        let number = |n: &str| n.parse().map_err(|_| format!("Bad number {}", n));
+        if left == "mask" {
+            return Ok(Instruction::Mask(right.parse()?));
+        }
+        let address = left
+            .strip_prefix("mem[")
+            .and_then(|a| a.strip_suffix(']'))
+            .ok_or_else(|| format!("Bad instruction {}", s))?;
+        Ok(Instruction::Write {
+            address: number(address)?,
+            value: number(right)?,
+        })
+    }
+}
+
+pub fn parse(input: &str) -> Result<Vec<Instruction>, String> {
+    input
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.trim().parse())
+        .collect()
+}
+
+/// Every write in the program, with the mask in force when it happened.
+fn writes(program: &[Instruction]) -> impl Iterator<Item = (Mask, u64, u64)> + '_ {
+    let mut mask = Mask::default();
+    program.iter().filter_map(move |i| match *i {
+        Instruction::Mask(m) => {
+            mask = m;
+            None
+        }
+        Instruction::Write { address, value } => Some((mask, address, value)),
+    })
+}
+
+/// The sum of memory after running the program on a version 1 chip.
+pub fn run_v1(program: &[Instruction]) -> u64 {
+    let mut memory: HashMap<u64, u64> = HashMap::new();
+    for (mask, address, value) in writes(program) {
+        memory.insert(address, mask.value(value));
+    }
+    memory.values().sum()
+}
+
+/// The sum of memory after running the program on a version 2 chip.
+///
+/// Goes through the writes from last to first, so that each write only
+/// counts for the addresses no later write covers.
+pub fn run_v2(program: &[Instruction]) -> u64 {
+    let writes: Vec<(Ternary, u64)> = writes(program)
+        .map(|(mask, address, value)| (mask.address(address), value))
+        .collect();
+
+    let mut covered: Vec<Ternary> = vec![];
+    let mut total = 0;
+    for &(addresses, value) in writes.iter().rev() {
+        let mut pieces = vec![addresses];
+        for c in &covered {
+            pieces = pieces.iter().flat_map(|p| p.subtract(c)).collect();
+            if pieces.is_empty() {
+                break;
+            }
+        }
+        total += value * pieces.iter().map(Ternary::size).sum::<u64>();
+        covered.extend(pieces);
+    }
+    total
+}
@@ -1,5 +1,7 @@
 use std::io::{self, Read};
 
+pub mod memory;
+
 pub fn read_stdin() -> String {
     let mut buffer = String::new();
     let stdin = io::stdin();
@@ -43,12 +45,12 @@ pub struct Point {
 
 impl Point {
     pub fn add(&mut self, dx: isize, dy: isize) {
-        self.x = self.x + dx;
-        self.y  = self.y + dy;
+        self.x += dx;
+        self.y += dy;
     }
 
     pub fn distance(&self) -> usize {
-        self.x.abs() as usize + self.y.abs() as usize
+        self.x.unsigned_abs() + self.y.unsigned_abs()
     }
 }
 
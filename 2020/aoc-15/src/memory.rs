@@ -0,0 +1,92 @@
+/// Marks a number that hasn't been spoken yet.
+const UNSEEN: u32 = u32::MAX;
+
+/// The elves' memory game: after the starting numbers, each turn's number is
+/// how many turns ago the previous number was last spoken before that, or 0
+/// if it was new.
+///
+/// Iterating gives the numbers spoken, turn by turn, for the given number of
+/// turns. Every number is below the turn count or one of the starting
+/// numbers, so the last turn each was spoken is kept in a flat table.
+pub struct Game {
+    start: Vec<u32>,
+    last_seen: Vec<u32>,
+    turn: u32,
+    turns: u32,
+    prev: u32,
+}
+
+impl Game {
+    pub fn new(start: &[u32], turns: u32) -> Self {
+        assert!(turns < UNSEEN, "Too many turns to fit in the table");
+        let size = start.iter().map(|&n| n + 1).fold(turns, u32::max);
+        Self {
+            start: start.to_vec(),
+            last_seen: vec![UNSEEN; size as usize],
+            turn: 0,
+            turns,
+            prev: 0,
+        }
+    }
+}
+
+impl Iterator for Game {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        if self.turn >= self.turns {
+            return None;
+        }
+        let turn = self.turn;
+        let spoken = match self.start.get(turn as usize) {
+            Some(&n) => n,
+            None => match self.last_seen[self.prev as usize] {
+                UNSEEN => 0,
+                seen => turn - 1 - seen,
+            },
+        };
+        if turn > 0 {
+            self.last_seen[self.prev as usize] = turn - 1;
+        }
+        self.prev = spoken;
+        self.turn += 1;
+        Some(spoken)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = (self.turns - self.turn) as usize;
+        (left, Some(left))
+    }
+}
+
+/// The number spoken on the last of `turns` turns.
+pub fn spoken_at(start: &[u32], turns: u32) -> Option<u32> {
+    Game::new(start, turns).last()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::spoken_at;
+
+    #[test]
+    fn examples_at_2020() {
+        let examples: [(&[u32], u32); 7] = [
+            (&[0, 3, 6], 436),
+            (&[1, 3, 2], 1),
+            (&[2, 1, 3], 10),
+            (&[1, 2, 3], 27),
+            (&[2, 3, 1], 78),
+            (&[3, 2, 1], 438),
+            (&[3, 1, 2], 1836),
+        ];
+        for (start, expected) in examples {
+            assert_eq!(spoken_at(start, 2020), Some(expected), "{:?}", start);
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn example_at_30_million() {
+        assert_eq!(spoken_at(&[0, 3, 6], 30_000_000), Some(175594));
+    }
+}
@@ -88,9 +88,3 @@ impl Rule {
         valid
     }
 }
-
-#[derive(Debug, Eq, PartialEq)]
-pub struct Constraint<'a> {
-    pub locked: bool,
-    pub possibilities: Vec<&'a Rule>,
-}
@@ -1,4 +1,7 @@
-use aoc::{read_stdin, Ticket, Rule, Constraint};
+use std::collections::BTreeSet;
+
+use aoc::{read_stdin, Ticket, Rule};
+use assign::assign;
 
 fn parse(input: String) -> (Vec<Rule>, Ticket, Vec<Ticket>) {
     let mut input = input.split("\n\n");
@@ -44,74 +47,24 @@ fn part_1(rules: &Vec<Rule>, other: &Vec<Ticket>) -> usize {
     total
 }
 
-fn solved(constraints: &Vec<Constraint>) -> bool {
-    for constraint in constraints {
-        if !constraint.locked {
-            return false
-        }
-    }
-    return true
-}
-
-fn step_solve<'a>(possibilities: &mut Vec<Constraint<'a>>) -> Vec<Constraint<'a>> {
-    let first_unitary_unlocked = possibilities
-        .iter()
-        .filter(|p| !p.locked && p.possibilities.len() == 1)
-        .next().unwrap();
-
-    let to_remove = first_unitary_unlocked.possibilities.first().unwrap();
-
-    possibilities.iter()
-        .map(|p| {
-            if p == first_unitary_unlocked || p.locked {
-                Constraint {
-                    locked: true,
-                    possibilities: p.possibilities.clone(),
-                }
-            } else {
-                Constraint {
-                    locked: false,
-                    possibilities: p.possibilities
-                        .iter()
-                        .map(|q| *q)
-                        .filter(|q| q != to_remove)
-                        .collect()
-                }
-            }
-        })
-        .collect()
-
-}
-
-fn part_2(rules: &Vec<Rule>, ticket: &Ticket, other: &Vec<Ticket>) -> usize {
+fn part_2(rules: &Vec<Rule>, ticket: &Ticket, other: &[Ticket]) -> usize {
     let other: Vec<&Ticket> = other
         .iter()
         .filter(|t| t.valid(rules))
         .collect();
 
-    let mut possibilities: Vec<Constraint> = (0..ticket.numbers.len()).map(|i| {
-        Constraint {
-            locked: false,
-            possibilities: rules.iter().filter(|rule| {
-                for ticket in &other {
-                    if !rule.valid(ticket.numbers[i]) {
-                        return false
-                    }
-                }
-                true
-            }).collect()
-        }
+    let candidates: Vec<BTreeSet<usize>> = (0..ticket.numbers.len()).map(|i| {
+        (0..rules.len()).filter(|&r| {
+            other.iter().all(|ticket| rules[r].valid(ticket.numbers[i]))
+        }).collect()
     }).collect();
 
-    while !solved(&possibilities) {
-        possibilities = step_solve(&mut possibilities);
-    }
+    let fields = assign(&candidates).unwrap();
 
     let mut x = 1;
-    for (i, constraint) in possibilities.iter().enumerate() {
-        let rule = constraint.possibilities.first().unwrap();
-        if rule.name.starts_with("departure") {
-            x *= ticket.numbers.iter().nth(i).unwrap()
+    for (i, &rule) in fields.iter().enumerate() {
+        if rules[rule].name.starts_with("departure") {
+            x *= ticket.numbers[i]
         }
     }
 
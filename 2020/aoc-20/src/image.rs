@@ -0,0 +1,473 @@
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+/// One of the 8 ways to rotate and flip a grid. Applied in the order: flip
+/// x, flip y, then transpose.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Orientation {
+    pub flip_x: bool,
+    pub flip_y: bool,
+    pub transpose: bool,
+}
+
+impl Orientation {
+    pub const IDENTITY: Orientation = Orientation {
+        flip_x: false,
+        flip_y: false,
+        transpose: false,
+    };
+
+    pub fn all() -> impl Iterator<Item = Orientation> {
+        (0..8).map(|i| Orientation {
+            flip_x: i & 1 != 0,
+            flip_y: i & 2 != 0,
+            transpose: i & 4 != 0,
+        })
+    }
+}
+
+/// A rectangular grid of on/off pixels.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid {
+    pub width: usize,
+    pub height: usize,
+    cells: Vec<bool>,
+}
+
+impl FromStr for Grid {
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<&str> = input.lines().map(|l| l.trim_end()).collect();
+        let width = lines.iter().map(|l| l.len()).max().ok_or(())?;
+        let mut cells = vec![false; width * lines.len()];
+        for (y, l) in lines.iter().enumerate() {
+            for (x, c) in l.chars().enumerate() {
+                match c {
+                    '#' => cells[y * width + x] = true,
+                    '.' | ' ' => (),
+                    _ => return Err(()),
+                }
+            }
+        }
+
+        Ok(Grid {
+            width,
+            height: lines.len(),
+            cells,
+        })
+    }
+}
+
+impl Grid {
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.cells[y * self.width + x]
+    }
+
+    /// A borrowed view of this grid in some orientation.
+    pub fn view(&self, orientation: Orientation) -> View<'_> {
+        View {
+            grid: self,
+            orientation,
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.cells.iter().filter(|&&c| c).count()
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        for y in 0..self.height {
+            out.extend((0..self.width).map(|x| if self.get(x, y) { '#' } else { '.' }));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// A grid seen through an orientation, without copying it.
+#[derive(Debug, Clone, Copy)]
+pub struct View<'a> {
+    grid: &'a Grid,
+    pub orientation: Orientation,
+}
+
+impl<'a> View<'a> {
+    pub fn width(&self) -> usize {
+        if self.orientation.transpose {
+            self.grid.height
+        } else {
+            self.grid.width
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        if self.orientation.transpose {
+            self.grid.width
+        } else {
+            self.grid.height
+        }
+    }
+
+    /// Maps a position in the view back to the position in the grid.
+    pub fn source(&self, x: usize, y: usize) -> (usize, usize) {
+        let o = self.orientation;
+        let x = if o.flip_x { self.width() - 1 - x } else { x };
+        let y = if o.flip_y { self.height() - 1 - y } else { y };
+        if o.transpose {
+            (y, x)
+        } else {
+            (x, y)
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        let (x, y) = self.source(x, y);
+        self.grid.get(x, y)
+    }
+
+    fn edge(&self, cells: impl Iterator<Item = (usize, usize)>) -> Edge {
+        cells.map(|(x, y)| self.get(x, y)).collect()
+    }
+
+    /// The top edge, read left to right.
+    pub fn top(&self) -> Edge {
+        self.edge((0..self.width()).map(|x| (x, 0)))
+    }
+
+    /// The bottom edge, read left to right.
+    pub fn bottom(&self) -> Edge {
+        let y = self.height() - 1;
+        self.edge((0..self.width()).map(|x| (x, y)))
+    }
+
+    /// The left edge, read top to bottom.
+    pub fn left(&self) -> Edge {
+        self.edge((0..self.height()).map(|y| (0, y)))
+    }
+
+    /// The right edge, read top to bottom.
+    pub fn right(&self) -> Edge {
+        let x = self.width() - 1;
+        self.edge((0..self.height()).map(|y| (x, y)))
+    }
+}
+
+/// The cells along one edge of a grid, in order.
+pub type Edge = Vec<bool>;
+
+#[derive(Debug, Clone)]
+pub struct Tile {
+    pub id: usize,
+    pub grid: Grid,
+}
+
+impl FromStr for Tile {
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (header, grid) = input.trim().split_once('\n').ok_or(())?;
+        let id = header
+            .trim_start_matches("Tile ")
+            .trim_end_matches(':')
+            .parse()
+            .map_err(|_| ())?;
+        Ok(Tile {
+            id,
+            grid: grid.parse()?,
+        })
+    }
+}
+
+/// A tile placed in the assembled image, by index into the tile list.
+#[derive(Debug, Clone, Copy)]
+pub struct Placement {
+    pub tile: usize,
+    pub orientation: Orientation,
+}
+
+/// Every tile's edges in every orientation, so the tiles that fit against
+/// a given edge can be looked up directly.
+struct EdgeIndex {
+    by_left: HashMap<Edge, Vec<Placement>>,
+    by_top: HashMap<Edge, Vec<Placement>>,
+}
+
+impl EdgeIndex {
+    fn new(tiles: &[Tile]) -> Self {
+        let mut by_left: HashMap<Edge, Vec<Placement>> = HashMap::new();
+        let mut by_top: HashMap<Edge, Vec<Placement>> = HashMap::new();
+        for (i, t) in tiles.iter().enumerate() {
+            for orientation in Orientation::all() {
+                let view = t.grid.view(orientation);
+                let p = Placement {
+                    tile: i,
+                    orientation,
+                };
+                by_left.entry(view.left()).or_default().push(p);
+                by_top.entry(view.top()).or_default().push(p);
+            }
+        }
+        EdgeIndex { by_left, by_top }
+    }
+}
+
+/// Tiles laid out in rows, each in the orientation that makes its edges
+/// match its neighbours'.
+#[derive(Debug, Clone)]
+pub struct Layout {
+    pub columns: usize,
+    pub rows: usize,
+    pub placements: Vec<Placement>,
+}
+
+impl Layout {
+    pub fn at(&self, column: usize, row: usize) -> Placement {
+        self.placements[row * self.columns + column]
+    }
+
+    /// The tiles in the four corners, clockwise from the top left.
+    pub fn corners(&self) -> [Placement; 4] {
+        let (c, r) = (self.columns - 1, self.rows - 1);
+        [self.at(0, 0), self.at(c, 0), self.at(c, r), self.at(0, r)]
+    }
+}
+
+/// Arranges the tiles into a `columns` wide rectangle where every pair of
+/// touching edges match, backtracking whenever a tile can't be continued.
+pub fn assemble(tiles: &[Tile], columns: usize) -> Option<Layout> {
+    if columns == 0 || !tiles.len().is_multiple_of(columns) {
+        return None;
+    }
+    let index = EdgeIndex::new(tiles);
+    let mut layout = Layout {
+        columns,
+        rows: tiles.len() / columns,
+        placements: vec![],
+    };
+    let mut used = vec![false; tiles.len()];
+    if place(tiles, &index, &mut layout, &mut used) {
+        Some(layout)
+    } else {
+        None
+    }
+}
+
+/// Tries every factorisation of the tile count, squarest first.
+pub fn assemble_any(tiles: &[Tile]) -> Option<Layout> {
+    let mut widths: Vec<usize> = (1..=tiles.len())
+        .filter(|&w| tiles.len().is_multiple_of(w))
+        .collect();
+    widths.sort_by_key(|&w| (w as isize - (tiles.len() / w) as isize).abs());
+    widths.into_iter().find_map(|w| assemble(tiles, w))
+}
+
+fn place(tiles: &[Tile], index: &EdgeIndex, layout: &mut Layout, used: &mut [bool]) -> bool {
+    let i = layout.placements.len();
+    if i == tiles.len() {
+        return true;
+    }
+    let (column, row) = (i % layout.columns, i / layout.columns);
+    let view = |p: Placement| tiles[p.tile].grid.view(p.orientation);
+
+    let above = (row > 0).then(|| view(layout.at(column, row - 1)).bottom());
+    let candidates: Vec<Placement> = if column > 0 {
+        let left = view(layout.placements[i - 1]).right();
+        index.by_left.get(&left).cloned().unwrap_or_default()
+    } else if let Some(above) = &above {
+        index.by_top.get(above).cloned().unwrap_or_default()
+    } else {
+        (0..tiles.len())
+            .flat_map(|tile| {
+                Orientation::all().map(move |orientation| Placement { tile, orientation })
+            })
+            .collect()
+    };
+
+    for p in candidates {
+        if used[p.tile] || above.as_ref().is_some_and(|a| view(p).top() != *a) {
+            continue;
+        }
+        used[p.tile] = true;
+        layout.placements.push(p);
+        if place(tiles, index, layout, used) {
+            return true;
+        }
+        layout.placements.pop();
+        used[p.tile] = false;
+    }
+    false
+}
+
+/// Joins the placed tiles into one image, dropping each tile's border.
+pub fn stitch(tiles: &[Tile], layout: &Layout) -> Grid {
+    let first = &tiles[layout.placements[0].tile].grid;
+    let (tw, th) = (first.width - 2, first.height - 2);
+    let width = tw * layout.columns;
+    let height = th * layout.rows;
+    let mut cells = vec![false; width * height];
+
+    for row in 0..layout.rows {
+        for column in 0..layout.columns {
+            let p = layout.at(column, row);
+            let view = tiles[p.tile].grid.view(p.orientation);
+            for y in 0..th {
+                for x in 0..tw {
+                    cells[(row * th + y) * width + column * tw + x] = view.get(x + 1, y + 1);
+                }
+            }
+        }
+    }
+
+    Grid {
+        width,
+        height,
+        cells,
+    }
+}
+
+/// Every position where `pattern` (in any orientation) has all its set
+/// pixels set in `image`, as the pattern's orientation and top left corner.
+pub fn find_pattern(image: &Grid, pattern: &Grid) -> Vec<(Orientation, usize, usize)> {
+    let image = image.view(Orientation::IDENTITY);
+    let mut found = vec![];
+    for orientation in Orientation::all() {
+        let p = pattern.view(orientation);
+        let pixels: Vec<(usize, usize)> = (0..p.height())
+            .flat_map(|y| (0..p.width()).map(move |x| (x, y)))
+            .filter(|&(x, y)| p.get(x, y))
+            .collect();
+        if p.width() > image.width() || p.height() > image.height() {
+            continue;
+        }
+        for y in 0..=image.height() - p.height() {
+            for x in 0..=image.width() - p.width() {
+                if pixels.iter().all(|&(px, py)| image.get(x + px, y + py)) {
+                    found.push((orientation, x, y));
+                }
+            }
+        }
+    }
+    found
+}
+
+/// The image pixels covered by the pattern, taking every occurrence in the
+/// orientation it is found most often in. Pixels shared by overlapping
+/// occurrences are only counted once.
+pub fn pattern_cover(image: &Grid, pattern: &Grid) -> HashSet<(usize, usize)> {
+    let found = find_pattern(image, pattern);
+    let best = Orientation::all()
+        .max_by_key(|&o| found.iter().filter(|f| f.0 == o).count())
+        .unwrap();
+
+    let p = pattern.view(best);
+    let mut covered = HashSet::new();
+    for &(_, x, y) in found.iter().filter(|f| f.0 == best) {
+        for py in 0..p.height() {
+            for px in 0..p.width() {
+                if p.get(px, py) {
+                    covered.insert((x + px, y + py));
+                }
+            }
+        }
+    }
+    covered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A pseudo-random image, the same every run.
+    fn noise(width: usize, height: usize) -> Grid {
+        let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+        let cells = (0..width * height)
+            .map(|_| {
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                seed & 1 == 1
+            })
+            .collect();
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Cuts `image` into `size` square tiles that share their borders with
+    /// their neighbours, like the puzzle's, each turned a different way.
+    fn cut(image: &Grid, size: usize) -> Vec<Tile> {
+        let (columns, rows) = (
+            (image.width - 1) / (size - 1),
+            (image.height - 1) / (size - 1),
+        );
+        (0..columns * rows)
+            .map(|i| {
+                let (x0, y0) = ((i % columns) * (size - 1), (i / columns) * (size - 1));
+                let piece = Grid {
+                    width: size,
+                    height: size,
+                    cells: (0..size * size)
+                        .map(|j| image.get(x0 + j % size, y0 + j / size))
+                        .collect(),
+                };
+                let view = piece.view(Orientation::all().nth(i % 8).unwrap());
+                Tile {
+                    id: 101 + i,
+                    grid: Grid {
+                        width: size,
+                        height: size,
+                        cells: (0..size * size)
+                            .map(|j| view.get(j % size, j / size))
+                            .collect(),
+                    },
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn tiles_wider_than_64() {
+        // Blank the shared borders past their first 16 cells, so edges only
+        // differ in cells that packing an edge into a `u64` would drop.
+        let mut image = noise(3 * 79 + 1, 3 * 79 + 1);
+        for y in 0..image.height {
+            for x in 0..image.width {
+                let across = y % 79 == 0 && x % 79 >= 16;
+                let down = x % 79 == 0 && y % 79 >= 16;
+                if across || down {
+                    image.cells[y * image.width + x] = false;
+                }
+            }
+        }
+        let tiles = cut(&image, 80);
+        let layout = assemble_any(&tiles).unwrap();
+
+        let corners: usize = layout.corners().iter().map(|p| tiles[p.tile].id).product();
+        assert_eq!(corners, 101 * 103 * 107 * 109);
+
+        // The stitched image is the original without the shared borders,
+        // turned some way.
+        let stitched = stitch(&tiles, &layout);
+        let kept: Vec<usize> = (0..image.width).filter(|n| n % 79 != 0).collect();
+        let inner = Grid {
+            width: kept.len(),
+            height: kept.len(),
+            cells: kept
+                .iter()
+                .flat_map(|&y| kept.iter().map(move |&x| (x, y)))
+                .map(|(x, y)| image.get(x, y))
+                .collect(),
+        };
+        assert!(Orientation::all().any(|o| {
+            let view = inner.view(o);
+            (0..stitched.height)
+                .all(|y| (0..stitched.width).all(|x| stitched.get(x, y) == view.get(x, y)))
+        }));
+        assert_eq!((stitched.width, stitched.height), (3 * 78, 3 * 78));
+        assert_eq!(stitched.count(), 27475);
+    }
+}
@@ -1,5 +1,6 @@
 use std::io::{self, Read};
-use std::str::FromStr;
+
+pub mod image;
 
 pub fn read_stdin() -> String {
     let mut buffer = String::new();
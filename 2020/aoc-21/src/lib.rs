@@ -1,5 +1,4 @@
 use std::io::{self, Read};
-use std::str::FromStr;
 
 pub fn read_stdin() -> String {
     let mut buffer = String::new();
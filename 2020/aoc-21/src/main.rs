@@ -1,85 +1,105 @@
 use aoc::read_stdin;
-use std::collections::{HashSet, HashMap};
+use assign::{assign, AssignError};
+use std::collections::{BTreeSet, HashMap, HashSet};
 
-
-fn is_solved(potential_ingredients: &HashMap<String, HashSet<String>>) -> bool {
-    for potentials in potential_ingredients.values() {
-        if potentials.len() > 1 {
-            return false
-        }
-    }
-    true
+struct Food<'a> {
+    ingredients: Vec<&'a str>,
+    allergens: Vec<&'a str>,
 }
 
-
-fn part_1(input: &str) -> usize {
-    let mut potential_ingredients: HashMap<&str, HashSet<&str>> = HashMap::new();
-    let mut recipes: Vec<Vec<&str>> = vec![];
-    let input = input.replace(")", "");
-
-    for line in input.lines() {
-        let mut s = line.split(" (contains ");
-        let ingredients: HashSet<&str> = s
-            .next().unwrap()
-            .split(" ")
-            .collect();
-
-        recipes.push(ingredients.iter().cloned().collect());
-
-        let allergens: Vec<&str> = s
-            .next().unwrap()
-            .split(", ")
-            .collect();
-
-        for allergen in allergens {
-            let mut x = ingredients.clone();
-            if let Some(existing) = potential_ingredients.get(&allergen) {
-                x = ingredients
-                    .intersection(existing)
-                    .map(|x| x.clone())
-                    .collect();
+fn parse(input: &str) -> Vec<Food<'_>> {
+    input
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|line| {
+            let (ingredients, allergens) = line
+                .trim()
+                .trim_end_matches(')')
+                .split_once(" (contains ")
+                .unwrap_or((line.trim(), ""));
+            Food {
+                ingredients: ingredients.split_whitespace().collect(),
+                allergens: allergens.split(", ").filter(|a| !a.is_empty()).collect(),
             }
-            potential_ingredients.insert(allergen, x);
-        }
-    }
-
-    let mut solved_allergens: HashMap<&str, &str> = HashMap::new();
+        })
+        .collect()
+}
 
-    while potential_ingredients.len() != solved_allergens.len() {
-        for (allergen, ingredients) in potential_ingredients.iter() {
-            if ingredients.len() == 1 {
-                solved_allergens.insert(allergen, ingredients.iter().next().unwrap());
-            };
-        }
+struct Solution<'a> {
+    /// How many times ingredients that can't contain any allergen appear.
+    safe: usize,
+    /// The ingredient containing each allergen, by allergen name.
+    dangerous: Vec<&'a str>,
+}
 
-        for ingredient in solved_allergens.values() {
-            for ingredients in potential_ingredients.values_mut() {
-                ingredients.remove(ingredient);
-            }
+fn solve<'a>(foods: &[Food<'a>]) -> Result<Solution<'a>, AssignError> {
+    // An allergen's ingredient has to be in every food listing it.
+    let mut possible: HashMap<&str, HashSet<&str>> = HashMap::new();
+    for food in foods {
+        let ingredients: HashSet<&str> = food.ingredients.iter().copied().collect();
+        for allergen in &food.allergens {
+            possible
+                .entry(allergen)
+                .and_modify(|p| p.retain(|i| ingredients.contains(i)))
+                .or_insert_with(|| ingredients.clone());
         }
     }
 
-    let solved_ingredients: HashSet<&str> = solved_allergens.values().map(|&x| x).collect();
-    let mut allergens: Vec<&str> = solved_allergens.keys().map(|x| *x).collect();
+    let mut allergens: Vec<&str> = possible.keys().copied().collect();
     allergens.sort();
-    let ingredients: Vec<&str> = allergens.iter().map(|a| *solved_allergens.get(a).unwrap()).collect();
-
-    // Part 2
-    dbg!(&ingredients);
+    let mut names: Vec<&str> = possible.values().flatten().copied().collect();
+    names.sort();
+    names.dedup();
 
-    recipes
+    let candidates: Vec<BTreeSet<usize>> = allergens
         .iter()
-        .map(|r| r
-             .iter()
-             .filter(|i| !solved_ingredients.contains(*i))
-             .count()
-        )
-        .sum()
-}
+        .map(|a| {
+            possible[a]
+                .iter()
+                .map(|i| names.binary_search(i).unwrap())
+                .collect()
+        })
+        .collect();
+    let dangerous: Vec<&str> = assign(&candidates)?.into_iter().map(|i| names[i]).collect();
+
+    let safe = foods
+        .iter()
+        .flat_map(|f| &f.ingredients)
+        .filter(|i| !dangerous.contains(i))
+        .count();
 
+    Ok(Solution { safe, dangerous })
+}
 
 fn main() {
     let input = read_stdin();
+    let foods = parse(&input);
+    let solution = solve(&foods).unwrap();
 
-    println!("Answer 1: {}", part_1(&input));
+    println!("Answer 1: {}", solution.safe);
+    println!("Answer 2: {}", solution.dangerous.join(","));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, solve};
+
+    #[test]
+    fn example() {
+        let foods = parse(include_str!("../input.txt"));
+        let solution = solve(&foods).unwrap();
+        assert_eq!(solution.safe, 5);
+        assert_eq!(solution.dangerous.join(","), "mxmxvkd,sqjhc,fvjkl");
+    }
+
+    #[test]
+    fn main_input() {
+        let foods = parse(include_str!("../main_input.txt"));
+        let solution = solve(&foods).unwrap();
+        assert_eq!(solution.safe, 2584);
+        assert_eq!(
+            solution.dangerous.join(","),
+            "fqhpsl,zxncg,clzpsl,zbbnj,jkgbvlxh,dzqc,ppj,glzb"
+        );
+    }
 }
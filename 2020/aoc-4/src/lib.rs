@@ -2,6 +2,8 @@ use std::collections::HashMap;
 use std::io::{self, Read};
 use std::str::FromStr;
 
+pub mod schema;
+
 pub fn read_stdin() -> String {
     let mut buffer = String::new();
     let stdin = io::stdin();
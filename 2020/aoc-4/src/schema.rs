@@ -0,0 +1,197 @@
+use regex::Regex;
+use std::fmt;
+use std::str::FromStr;
+
+/// A check on a single field's value.
+#[derive(Debug, Clone)]
+pub enum Rule {
+    /// Exactly this many characters.
+    Len(usize),
+    /// A whole number within the inclusive range.
+    Range(u64, u64),
+    /// A whole number followed by one of the units, within that unit's
+    /// inclusive range.
+    Units(Vec<(String, u64, u64)>),
+    /// One of a fixed set of values.
+    OneOf(Vec<String>),
+    /// Matches the pattern. Patterns aren't anchored unless they say so.
+    Pattern(Regex),
+}
+
+impl Rule {
+    pub fn check(&self, value: &str) -> bool {
+        let in_range = |v: &str, lo: u64, hi: u64| {
+            v.bytes().all(|b| b.is_ascii_digit())
+                && v.parse::<u64>().is_ok_and(|n| lo <= n && n <= hi)
+        };
+        match self {
+            Rule::Len(n) => value.chars().count() == *n,
+            Rule::Range(lo, hi) => in_range(value, *lo, *hi),
+            Rule::Units(units) => units.iter().any(|(unit, lo, hi)| {
+                value
+                    .strip_suffix(unit.as_str())
+                    .is_some_and(|v| in_range(v, *lo, *hi))
+            }),
+            Rule::OneOf(values) => values.iter().any(|v| v == value),
+            Rule::Pattern(re) => re.is_match(value),
+        }
+    }
+}
+
+/// Rules are written the same way they are read, e.g. `range=1920-2002`.
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rule::Len(n) => write!(f, "len={}", n),
+            Rule::Range(lo, hi) => write!(f, "range={}-{}", lo, hi),
+            Rule::Units(units) => {
+                let units: Vec<String> = units
+                    .iter()
+                    .map(|(unit, lo, hi)| format!("{}-{}{}", lo, hi, unit))
+                    .collect();
+                write!(f, "units={}", units.join(","))
+            }
+            Rule::OneOf(values) => write!(f, "oneof={}", values.join(",")),
+            Rule::Pattern(re) => write!(f, "regex={}", re.as_str()),
+        }
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bad = || format!("Bad rule {}", s);
+        let range = |r: &str| -> Result<(u64, u64), String> {
+            let (lo, hi) = r.split_once('-').ok_or_else(bad)?;
+            Ok((
+                lo.parse().map_err(|_| bad())?,
+                hi.parse().map_err(|_| bad())?,
+            ))
+        };
+
+        let (kind, arg) = s.split_once('=').ok_or_else(bad)?;
+        Ok(match kind {
+            "len" => Rule::Len(arg.parse().map_err(|_| bad())?),
+            "range" => {
+                let (lo, hi) = range(arg)?;
+                Rule::Range(lo, hi)
+            }
+            "units" => Rule::Units(
+                arg.split(',')
+                    .map(|u| {
+                        let split = u.rfind(|c: char| c.is_ascii_digit()).ok_or_else(bad)? + 1;
+                        let (lo, hi) = range(&u[..split])?;
+                        Ok((u[split..].to_string(), lo, hi))
+                    })
+                    .collect::<Result<_, String>>()?,
+            ),
+            "oneof" => Rule::OneOf(arg.split(',').map(String::from).collect()),
+            "regex" => Rule::Pattern(Regex::new(arg).map_err(|e| e.to_string())?),
+            _ => return Err(bad()),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Field {
+    pub name: String,
+    pub required: bool,
+    pub rules: Vec<Rule>,
+}
+
+/// The fields a record may have, one per line:
+///
+/// ```text
+/// byr required len=4 range=1920-2002
+/// hgt required units=150-193cm,59-76in
+/// cid optional
+/// ```
+///
+/// Fields that aren't listed aren't allowed.
+#[derive(Debug, Clone)]
+pub struct Schema {
+    pub fields: Vec<Field>,
+}
+
+impl FromStr for Schema {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with("//"))
+            .map(|l| {
+                let mut words = l.split_whitespace();
+                let name = words.next().unwrap().to_string();
+                let required = match words.next() {
+                    Some("required") => true,
+                    Some("optional") => false,
+                    _ => return Err(format!("Field {} must be required or optional", name)),
+                };
+                let rules = words.map(str::parse).collect::<Result<_, _>>()?;
+                Ok(Field {
+                    name,
+                    required,
+                    rules,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Schema { fields })
+    }
+}
+
+/// Everything wrong with one record.
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    pub missing: Vec<String>,
+    pub unknown: Vec<String>,
+    /// Each field whose value broke a rule, with the rule.
+    pub failed: Vec<(String, String)>,
+}
+
+impl Report {
+    pub fn is_valid(&self) -> bool {
+        self.missing.is_empty() && self.unknown.is_empty() && self.failed.is_empty()
+    }
+}
+
+impl Schema {
+    pub fn validate(&self, record: &[(String, String)]) -> Report {
+        let mut report = Report::default();
+        for field in &self.fields {
+            if field.required && !record.iter().any(|(k, _)| *k == field.name) {
+                report.missing.push(field.name.clone());
+            }
+        }
+        for (key, value) in record {
+            let Some(field) = self.fields.iter().find(|f| f.name == *key) else {
+                report.unknown.push(key.clone());
+                continue;
+            };
+            for rule in field.rules.iter().filter(|r| !r.check(value)) {
+                report.failed.push((key.clone(), rule.to_string()));
+            }
+        }
+        report
+    }
+}
+
+/// Splits the input into records of `key:value` pairs separated by
+/// whitespace, with a blank line between records. A pair without a `:` is
+/// kept as a key with an empty value.
+pub fn parse_records(input: &str) -> Vec<Vec<(String, String)>> {
+    input
+        .split("\n\n")
+        .filter(|r| !r.trim().is_empty())
+        .map(|r| {
+            r.split_whitespace()
+                .map(|pair| {
+                    let (k, v) = pair.split_once(':').unwrap_or((pair, ""));
+                    (k.to_string(), v.to_string())
+                })
+                .collect()
+        })
+        .collect()
+}
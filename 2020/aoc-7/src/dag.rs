@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+pub type NodeId = usize;
+
+/// Bags and what each must directly contain, with every bag interned to an
+/// id. Parsing fails if a bag ends up inside itself, so the graph is always
+/// acyclic.
+#[derive(Debug, Clone)]
+pub struct Dag {
+    names: Vec<String>,
+    ids: HashMap<String, NodeId>,
+    /// The bags directly inside each bag, with how many of each.
+    children: Vec<Vec<(NodeId, usize)>>,
+    parents: Vec<Vec<NodeId>>,
+    /// Every bag comes before the bags inside it.
+    order: Vec<NodeId>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DagError {
+    BadRule(String),
+    BadCount(String),
+    /// The bags on a cycle, each containing the next and the last containing
+    /// the first.
+    Cycle(Vec<String>),
+}
+
+impl FromStr for Dag {
+    type Err = DagError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut dag = Dag {
+            names: vec![],
+            ids: HashMap::new(),
+            children: vec![],
+            parents: vec![],
+            order: vec![],
+        };
+
+        for line in input.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let (bag, contents) = line
+                .trim_end_matches('.')
+                .split_once(" bags contain ")
+                .ok_or_else(|| DagError::BadRule(line.into()))?;
+            let bag = dag.intern(bag);
+            if contents == "no other bags" {
+                continue;
+            }
+            for content in contents.split(", ") {
+                let (count, inner) = content
+                    .split_once(' ')
+                    .ok_or_else(|| DagError::BadRule(line.into()))?;
+                let count = count
+                    .parse()
+                    .map_err(|_| DagError::BadCount(content.into()))?;
+                let inner = inner
+                    .strip_suffix(" bags")
+                    .or_else(|| inner.strip_suffix(" bag"))
+                    .ok_or_else(|| DagError::BadRule(line.into()))?;
+                let inner = dag.intern(inner);
+                dag.children[bag].push((inner, count));
+                dag.parents[inner].push(bag);
+            }
+        }
+
+        dag.order = dag.topological_order()?;
+        Ok(dag)
+    }
+}
+
+impl Dag {
+    fn intern(&mut self, name: &str) -> NodeId {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        self.names.push(name.into());
+        self.children.push(vec![]);
+        self.parents.push(vec![]);
+        self.ids.insert(name.into(), self.names.len() - 1);
+        self.names.len() - 1
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn id(&self, name: &str) -> Option<NodeId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: NodeId) -> &str {
+        &self.names[id]
+    }
+
+    pub fn children(&self, id: NodeId) -> &[(NodeId, usize)] {
+        &self.children[id]
+    }
+
+    /// Every bag, outermost first.
+    pub fn order(&self) -> &[NodeId] {
+        &self.order
+    }
+
+    /// Orders the bags so each comes before everything inside it, or finds a
+    /// bag that ends up inside itself.
+    fn topological_order(&self) -> Result<Vec<NodeId>, DagError> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum State {
+            New,
+            Open,
+            Done,
+        }
+
+        let mut state = vec![State::New; self.len()];
+        let mut finished = Vec::with_capacity(self.len());
+        for root in 0..self.len() {
+            if state[root] != State::New {
+                continue;
+            }
+            state[root] = State::Open;
+            // The path being explored, with how many children each bag on it
+            // has been through.
+            let mut path = vec![(root, 0)];
+            while let Some((at, next)) = path.last_mut() {
+                let at = *at;
+                let Some(&(child, _)) = self.children[at].get(*next) else {
+                    state[at] = State::Done;
+                    finished.push(at);
+                    path.pop();
+                    continue;
+                };
+                *next += 1;
+                match state[child] {
+                    State::New => {
+                        state[child] = State::Open;
+                        path.push((child, 0));
+                    }
+                    State::Open => {
+                        let start = path.iter().position(|&(id, _)| id == child).unwrap();
+                        return Err(DagError::Cycle(
+                            path[start..]
+                                .iter()
+                                .map(|&(id, _)| self.names[id].clone())
+                                .collect(),
+                        ));
+                    }
+                    State::Done => (),
+                }
+            }
+        }
+        finished.reverse();
+        Ok(finished)
+    }
+
+    /// Every bag that eventually contains `id`, not counting `id` itself.
+    pub fn ancestors(&self, id: NodeId) -> Vec<NodeId> {
+        let mut seen = vec![false; self.len()];
+        let mut stack = vec![id];
+        let mut found = vec![];
+        while let Some(at) = stack.pop() {
+            for &parent in &self.parents[at] {
+                if !seen[parent] {
+                    seen[parent] = true;
+                    found.push(parent);
+                    stack.push(parent);
+                }
+            }
+        }
+        found
+    }
+
+    /// How many bags each bag holds in total, counting bags inside bags.
+    ///
+    /// Works innermost first, so every bag's total is built once from the
+    /// totals of the bags directly inside it.
+    pub fn descendant_counts(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.len()];
+        for &bag in self.order.iter().rev() {
+            counts[bag] = self.children[bag]
+                .iter()
+                .map(|&(child, n)| n * (1 + counts[child]))
+                .sum();
+        }
+        counts
+    }
+
+    /// How many bags `id` holds in total.
+    pub fn descendants(&self, id: NodeId) -> usize {
+        self.descendant_counts()[id]
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::BTreeSet;
+
+/// Why no assignment exists.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssignError {
+    /// Every candidate for this slot was taken by other slots.
+    NoCandidates(usize),
+    /// Every slot still has candidates, but there aren't enough distinct
+    /// values to go round.
+    NoMatching,
+}
+
+/// Gives every slot a different value out of its candidates.
+///
+/// Slots with a single candidate are settled first, removing that value
+/// from every other slot, until no more can be settled that way. Anything
+/// left over is settled by finding a maximum matching between the remaining
+/// slots and values, which succeeds whenever an assignment exists at all.
+pub fn assign(candidates: &[BTreeSet<usize>]) -> Result<Vec<usize>, AssignError> {
+    let mut candidates = candidates.to_vec();
+    let mut assigned: Vec<Option<usize>> = vec![None; candidates.len()];
+
+    let mut queue: Vec<usize> = (0..candidates.len()).collect();
+    while let Some(slot) = queue.pop() {
+        if assigned[slot].is_some() {
+            continue;
+        }
+        let value = match candidates[slot].len() {
+            0 => return Err(AssignError::NoCandidates(slot)),
+            1 => *candidates[slot].iter().next().unwrap(),
+            _ => continue,
+        };
+        assigned[slot] = Some(value);
+        for (other, c) in candidates.iter_mut().enumerate() {
+            if other != slot && c.remove(&value) && c.len() <= 1 {
+                queue.push(other);
+            }
+        }
+    }
+
+    let open: Vec<usize> = (0..candidates.len())
+        .filter(|&s| assigned[s].is_none())
+        .collect();
+    let values = candidates
+        .iter()
+        .filter_map(|c| c.iter().next_back())
+        .max()
+        .map_or(0, |v| v + 1);
+    let mut owner: Vec<Option<usize>> = vec![None; values];
+    for &slot in &open {
+        if !augment(slot, &candidates, &mut owner, &mut vec![false; values]) {
+            return Err(AssignError::NoMatching);
+        }
+    }
+    for (value, slot) in owner.into_iter().enumerate() {
+        if let Some(slot) = slot {
+            assigned[slot] = Some(value);
+        }
+    }
+
+    Ok(assigned.into_iter().map(Option::unwrap).collect())
+}
+
+/// Tries to give `slot` a value, moving other slots to different values
+/// if need be (Kuhn's algorithm).
+fn augment(
+    slot: usize,
+    candidates: &[BTreeSet<usize>],
+    owner: &mut [Option<usize>],
+    seen: &mut [bool],
+) -> bool {
+    for &value in &candidates[slot] {
+        if seen[value] {
+            continue;
+        }
+        seen[value] = true;
+        let free = match owner[value] {
+            None => true,
+            Some(other) => augment(other, candidates, owner, seen),
+        };
+        if free {
+            owner[value] = Some(slot);
+            return true;
+        }
+    }
+    false
+}
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+pub type CaveId = usize;
+
+/// The cave system, with every cave interned to an id. Small caves also get
+/// a bit each, so the set of small caves visited fits in a `u64`.
+#[derive(Debug, Clone)]
+pub struct Caves {
+    names: Vec<String>,
+    neighbours: Vec<Vec<CaveId>>,
+    /// The bit of each small cave, or `None` for big caves.
+    bits: Vec<Option<u32>>,
+    pub start: CaveId,
+    pub end: CaveId,
+}
+
+#[derive(Debug)]
+pub enum CaveError {
+    BadLine(String),
+    MissingCave(&'static str),
+    /// Two big caves are connected, so paths could go back and forth between
+    /// them forever.
+    BigCavesConnected(String, String),
+    TooManySmallCaves,
+}
+
+impl FromStr for Caves {
+    type Err = CaveError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut ids: HashMap<&str, CaveId> = HashMap::new();
+        let mut caves = Caves {
+            names: vec![],
+            neighbours: vec![],
+            bits: vec![],
+            start: 0,
+            end: 0,
+        };
+
+        for line in input.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let (a, b) = line
+                .split_once('-')
+                .ok_or_else(|| CaveError::BadLine(line.into()))?;
+            let [a, b] = [a, b].map(|name| {
+                *ids.entry(name).or_insert_with(|| {
+                    caves.names.push(name.into());
+                    caves.neighbours.push(vec![]);
+                    caves.bits.push(None);
+                    caves.names.len() - 1
+                })
+            });
+            caves.neighbours[a].push(b);
+            caves.neighbours[b].push(a);
+        }
+
+        let mut small = 0;
+        for (id, name) in caves.names.iter().enumerate() {
+            if !name.chars().all(|c| c.is_ascii_uppercase()) {
+                if small == u64::BITS {
+                    return Err(CaveError::TooManySmallCaves);
+                }
+                caves.bits[id] = Some(small);
+                small += 1;
+            }
+        }
+        for (a, ns) in caves.neighbours.iter().enumerate() {
+            if let Some(&b) = ns.iter().find(|&&b| caves.is_big(a) && caves.is_big(b)) {
+                return Err(CaveError::BigCavesConnected(
+                    caves.names[a].clone(),
+                    caves.names[b].clone(),
+                ));
+            }
+        }
+
+        caves.start = *ids.get("start").ok_or(CaveError::MissingCave("start"))?;
+        caves.end = *ids.get("end").ok_or(CaveError::MissingCave("end"))?;
+        Ok(caves)
+    }
+}
+
+impl Caves {
+    pub fn name(&self, id: CaveId) -> &str {
+        &self.names[id]
+    }
+
+    fn is_big(&self, id: CaveId) -> bool {
+        self.bits[id].is_none()
+    }
+
+    /// Where a path at `visited` with `revisits` left can go next from
+    /// `from`, with the visited set and revisits left once it's there.
+    fn steps(&self, from: CaveId, visited: u64, revisits: usize) -> Vec<(CaveId, u64, usize)> {
+        self.neighbours[from]
+            .iter()
+            .filter(|&&to| to != self.start)
+            .filter_map(|&to| match self.bits[to] {
+                None => Some((to, visited, revisits)),
+                Some(bit) if visited & 1 << bit == 0 => Some((to, visited | 1 << bit, revisits)),
+                Some(_) if revisits > 0 && to != self.end => Some((to, visited, revisits - 1)),
+                Some(_) => None,
+            })
+            .collect()
+    }
+
+    fn start_mask(&self) -> u64 {
+        self.bits[self.start].map_or(0, |bit| 1 << bit)
+    }
+
+    /// How many paths lead from start to end, visiting small caves at most
+    /// once except for up to `revisits` extra visits in total. The start is
+    /// never revisited and a path stops as soon as it reaches the end.
+    pub fn count_paths(&self, revisits: usize) -> usize {
+        let mut memo = HashMap::new();
+        self.count_from(self.start, self.start_mask(), revisits, &mut memo)
+    }
+
+    fn count_from(
+        &self,
+        at: CaveId,
+        visited: u64,
+        revisits: usize,
+        memo: &mut HashMap<(CaveId, u64, usize), usize>,
+    ) -> usize {
+        if at == self.end {
+            return 1;
+        }
+        if let Some(&n) = memo.get(&(at, visited, revisits)) {
+            return n;
+        }
+        let n = self
+            .steps(at, visited, revisits)
+            .into_iter()
+            .map(|(to, visited, revisits)| self.count_from(to, visited, revisits, memo))
+            .sum();
+        memo.insert((at, visited, revisits), n);
+        n
+    }
+
+    /// Every path counted by `count_paths`, one at a time, as cave ids from
+    /// start to end.
+    pub fn paths(&self, revisits: usize) -> impl Iterator<Item = Vec<CaveId>> + '_ {
+        let mut stack = vec![(
+            self.start,
+            self.steps(self.start, self.start_mask(), revisits),
+        )];
+        std::iter::from_fn(move || {
+            while let Some((_, next)) = stack.last_mut() {
+                let Some((to, visited, revisits)) = next.pop() else {
+                    stack.pop();
+                    continue;
+                };
+                if to == self.end {
+                    let mut path: Vec<CaveId> = stack.iter().map(|(at, _)| *at).collect();
+                    path.push(to);
+                    return Some(path);
+                }
+                stack.push((to, self.steps(to, visited, revisits)));
+            }
+            None
+        })
+    }
+
+    pub fn render_path(&self, path: &[CaveId]) -> String {
+        path.iter()
+            .map(|&id| self.name(id))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
@@ -0,0 +1,192 @@
+use std::fmt;
+use std::ops::Add;
+use std::str::FromStr;
+
+/// A regular number together with how many pairs enclose it.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+struct Cell {
+    depth: u8,
+    value: u32,
+}
+
+/// A snailfish number stored as its regular numbers in left-to-right order.
+///
+/// The tree shape is fully determined by the depths, so adding two numbers is
+/// just concatenating their cells and bumping every depth by one, and
+/// reduction only ever touches neighbouring cells.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Number {
+    cells: Vec<Cell>,
+}
+
+impl Number {
+    /// Wraps the concatenated cells of two numbers in a new pair and reduces.
+    fn nest(mut cells: Vec<Cell>) -> Self {
+        for c in cells.iter_mut() {
+            c.depth += 1;
+        }
+        let mut n = Self { cells };
+        n.reduce();
+        n
+    }
+
+    /// Applies explodes and splits until neither is possible.
+    pub fn reduce(&mut self) {
+        loop {
+            if let Some(i) = self.cells.iter().position(|c| c.depth > 4) {
+                self.explode(i);
+            } else if let Some(i) = self.cells.iter().position(|c| c.value >= 10) {
+                self.split(i);
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn explode(&mut self, i: usize) {
+        let Cell { depth, value: l } = self.cells[i];
+        let r = self.cells[i + 1].value;
+
+        if i > 0 {
+            self.cells[i - 1].value += l;
+        }
+        if let Some(next) = self.cells.get_mut(i + 2) {
+            next.value += r;
+        }
+
+        self.cells[i] = Cell {
+            depth: depth - 1,
+            value: 0,
+        };
+        self.cells.remove(i + 1);
+    }
+
+    fn split(&mut self, i: usize) {
+        let Cell { depth, value } = self.cells[i];
+        self.cells[i] = Cell {
+            depth: depth + 1,
+            value: value / 2,
+        };
+        self.cells.insert(
+            i + 1,
+            Cell {
+                depth: depth + 1,
+                value: value - value / 2,
+            },
+        );
+    }
+
+    pub fn magnitude(&self) -> usize {
+        let mut stack: Vec<(u8, usize)> = Vec::with_capacity(self.cells.len());
+        for c in &self.cells {
+            stack.push((c.depth, c.value as usize));
+            while let [.., (da, a), (db, b)] = stack[..] {
+                if da != db {
+                    break;
+                }
+                stack.truncate(stack.len() - 2);
+                stack.push((da - 1, 3 * a + 2 * b));
+            }
+        }
+        stack.first().map_or(0, |&(_, v)| v)
+    }
+
+    fn write_from(&self, f: &mut fmt::Formatter<'_>, i: &mut usize, depth: u8) -> fmt::Result {
+        let c = self.cells[*i];
+        if c.depth == depth {
+            *i += 1;
+            return write!(f, "{}", c.value);
+        }
+        write!(f, "[")?;
+        self.write_from(f, i, depth + 1)?;
+        write!(f, ",")?;
+        self.write_from(f, i, depth + 1)?;
+        write!(f, "]")
+    }
+}
+
+impl Add for Number {
+    type Output = Number;
+
+    fn add(mut self, other: Self) -> Self::Output {
+        self.cells.extend(other.cells);
+        Number::nest(self.cells)
+    }
+}
+
+impl Add for &Number {
+    type Output = Number;
+
+    fn add(self, other: Self) -> Self::Output {
+        let mut cells = Vec::with_capacity(self.cells.len() + other.cells.len());
+        cells.extend_from_slice(&self.cells);
+        cells.extend_from_slice(&other.cells);
+        Number::nest(cells)
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.cells.is_empty() {
+            return Ok(());
+        }
+        self.write_from(f, &mut 0, 0)
+    }
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    cells: Vec<Cell>,
+}
+
+impl<'a> Parser<'a> {
+    fn expect(&mut self, b: u8) -> Result<(), ()> {
+        if self.bytes.get(self.pos) == Some(&b) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+
+    fn element(&mut self, depth: u8) -> Result<(), ()> {
+        if self.bytes.get(self.pos) == Some(&b'[') {
+            self.pos += 1;
+            self.element(depth + 1)?;
+            self.expect(b',')?;
+            self.element(depth + 1)?;
+            return self.expect(b']');
+        }
+
+        let start = self.pos;
+        while self.bytes.get(self.pos).is_some_and(u8::is_ascii_digit) {
+            self.pos += 1;
+        }
+        let value = std::str::from_utf8(&self.bytes[start..self.pos])
+            .map_err(|_| ())?
+            .parse()
+            .map_err(|_| ())?;
+        self.cells.push(Cell { depth, value });
+        Ok(())
+    }
+}
+
+impl FromStr for Number {
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            bytes: input.trim().as_bytes(),
+            pos: 0,
+            cells: vec![],
+        };
+        parser.element(0)?;
+        if parser.pos != parser.bytes.len() {
+            return Err(());
+        }
+        Ok(Self {
+            cells: parser.cells,
+        })
+    }
+}
@@ -0,0 +1,318 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+
+/// How many beacons two scanners must agree on to be considered overlapping.
+pub const OVERLAP: usize = 12;
+
+/// Overlapping scanners share at least the distances between every pair of
+/// their common beacons.
+const SHARED_DISTANCES: usize = OVERLAP * (OVERLAP - 1) / 2;
+
+#[derive(Clone, Copy, Hash, Eq, PartialEq)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+    pub z: isize,
+}
+
+impl fmt::Debug for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({},{},{})", self.x, self.y, self.z)
+    }
+}
+
+impl Point {
+    pub fn new(x: isize, y: isize, z: isize) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn origin() -> Self {
+        Self::new(0, 0, 0)
+    }
+
+    pub fn mag(&self) -> isize {
+        self.x.abs() + self.y.abs() + self.z.abs()
+    }
+
+    pub fn euclid(&self, other: &Self) -> isize {
+        (self.x - other.x).pow(2) + (self.y - other.y).pow(2) + (self.z - other.z).pow(2)
+    }
+
+    fn coords(&self) -> [isize; 3] {
+        [self.x, self.y, self.z]
+    }
+}
+
+impl std::ops::Add for Point {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            x: self.x + other.x,
+            y: self.y + other.y,
+            z: self.z + other.z,
+        }
+    }
+}
+
+impl std::ops::Sub for Point {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self {
+            x: self.x - other.x,
+            y: self.y - other.y,
+            z: self.z - other.z,
+        }
+    }
+}
+
+impl std::ops::Neg for Point {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::origin() - self
+    }
+}
+
+/// A rotation by whole quarter turns, as a matrix with a single `1` or `-1`
+/// in each row.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Rotation([[isize; 3]; 3]);
+
+/// The 24 ways a scanner can be facing: every signed permutation matrix with
+/// determinant one.
+pub const ROTATIONS: [Rotation; 24] = rotations();
+
+const fn rotations() -> [Rotation; 24] {
+    const PERMUTATIONS: [([usize; 3], isize); 6] = [
+        ([0, 1, 2], 1),
+        ([0, 2, 1], -1),
+        ([1, 0, 2], -1),
+        ([1, 2, 0], 1),
+        ([2, 0, 1], 1),
+        ([2, 1, 0], -1),
+    ];
+
+    let mut out = [Rotation([[0; 3]; 3]); 24];
+    let mut n = 0;
+    let mut p = 0;
+    while p < PERMUTATIONS.len() {
+        let (axes, parity) = PERMUTATIONS[p];
+        let mut signs: isize = 0;
+        while signs < 8 {
+            let sign = [1 - 2 * (signs & 1), 1 - (signs & 2), 1 - (signs & 4) / 2];
+            if sign[0] * sign[1] * sign[2] == parity {
+                let mut m = [[0; 3]; 3];
+                m[0][axes[0]] = sign[0];
+                m[1][axes[1]] = sign[1];
+                m[2][axes[2]] = sign[2];
+                out[n] = Rotation(m);
+                n += 1;
+            }
+            signs += 1;
+        }
+        p += 1;
+    }
+    out
+}
+
+impl Rotation {
+    pub const IDENTITY: Rotation = Rotation([[1, 0, 0], [0, 1, 0], [0, 0, 1]]);
+
+    pub fn apply(&self, p: Point) -> Point {
+        let c = p.coords();
+        let row = |r: [isize; 3]| r[0] * c[0] + r[1] * c[1] + r[2] * c[2];
+        Point::new(row(self.0[0]), row(self.0[1]), row(self.0[2]))
+    }
+
+    /// The rotation that applies `other` first and then `self`.
+    pub fn then(&self, other: &Rotation) -> Rotation {
+        let mut m = [[0; 3]; 3];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = (0..3).map(|k| self.0[i][k] * other.0[k][j]).sum();
+            }
+        }
+        Rotation(m)
+    }
+}
+
+/// Maps points seen by one scanner into the frame of another.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Transform {
+    pub rotation: Rotation,
+    /// Where the scanner itself is in the other frame.
+    pub translation: Point,
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform {
+        rotation: Rotation::IDENTITY,
+        translation: Point { x: 0, y: 0, z: 0 },
+    };
+
+    pub fn apply(&self, p: Point) -> Point {
+        self.rotation.apply(p) + self.translation
+    }
+
+    /// The transform that applies `inner` first and then `self`.
+    pub fn then(&self, inner: &Transform) -> Transform {
+        Transform {
+            rotation: self.rotation.then(&inner.rotation),
+            translation: self.apply(inner.translation),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Scanner {
+    pub id: usize,
+    pub beacons: Vec<Point>,
+    /// How often each squared distance between two beacons occurs. These
+    /// don't change with the scanner's position or facing.
+    fingerprint: HashMap<isize, usize>,
+}
+
+impl FromStr for Scanner {
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut lines = input.lines();
+        let id = lines
+            .next()
+            .ok_or(())?
+            .trim()
+            .trim_start_matches("--- scanner ")
+            .trim_end_matches(" ---")
+            .parse()
+            .map_err(|_| ())?;
+
+        let beacons = lines
+            .map(|l| {
+                let p: Vec<isize> = l
+                    .trim()
+                    .split(',')
+                    .map(|c| c.parse().map_err(|_| ()))
+                    .collect::<Result<_, _>>()?;
+                match p[..] {
+                    [x, y, z] => Ok(Point::new(x, y, z)),
+                    _ => Err(()),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self::new(id, beacons))
+    }
+}
+
+impl Scanner {
+    pub fn new(id: usize, beacons: Vec<Point>) -> Self {
+        let mut fingerprint = HashMap::new();
+        for (i, a) in beacons.iter().enumerate() {
+            for b in &beacons[i + 1..] {
+                *fingerprint.entry(a.euclid(b)).or_insert(0) += 1;
+            }
+        }
+        Self {
+            id,
+            beacons,
+            fingerprint,
+        }
+    }
+
+    /// How many beacon distances the two scanners have in common.
+    pub fn shared_distances(&self, other: &Self) -> usize {
+        self.fingerprint
+            .iter()
+            .map(|(d, n)| other.fingerprint.get(d).map_or(0, |m| *n.min(m)))
+            .sum()
+    }
+
+    /// The transform taking `other`'s points into this scanner's frame, if at
+    /// least `OVERLAP` beacons line up under it.
+    pub fn transform_to(&self, other: &Self) -> Option<Transform> {
+        if self.shared_distances(other) < SHARED_DISTANCES {
+            return None;
+        }
+
+        for rotation in ROTATIONS {
+            let rotated: Vec<Point> = other.beacons.iter().map(|&p| rotation.apply(p)).collect();
+            let mut offsets: HashMap<Point, usize> = HashMap::new();
+            for a in &self.beacons {
+                for &b in &rotated {
+                    let n = offsets.entry(*a - b).or_insert(0);
+                    *n += 1;
+                    if *n >= OVERLAP {
+                        return Some(Transform {
+                            rotation,
+                            translation: *a - b,
+                        });
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Every scanner placed in the frame of the first one.
+#[derive(Clone, Debug)]
+pub struct Alignment {
+    /// For each scanner, the transform into the first scanner's frame.
+    pub transforms: Vec<Transform>,
+    /// The overlaps used to place the scanners, as `(from, to, transform)`
+    /// where the transform takes `to`'s points into `from`'s frame.
+    pub links: Vec<(usize, usize, Transform)>,
+}
+
+impl Alignment {
+    /// Places every scanner relative to the first, walking outwards through
+    /// overlapping pairs. Fails if some scanner can't be reached.
+    pub fn new(scanners: &[Scanner]) -> Option<Self> {
+        let mut transforms: Vec<Option<Transform>> = vec![None; scanners.len()];
+        let mut links = vec![];
+        let mut queue = VecDeque::new();
+        if !scanners.is_empty() {
+            transforms[0] = Some(Transform::IDENTITY);
+            queue.push_back(0);
+        }
+
+        while let Some(from) = queue.pop_front() {
+            let placed = transforms[from]?;
+            for to in 0..scanners.len() {
+                if transforms[to].is_some() {
+                    continue;
+                }
+                if let Some(t) = scanners[from].transform_to(&scanners[to]) {
+                    transforms[to] = Some(placed.then(&t));
+                    links.push((from, to, t));
+                    queue.push_back(to);
+                }
+            }
+        }
+
+        Some(Self {
+            transforms: transforms.into_iter().collect::<Option<_>>()?,
+            links,
+        })
+    }
+
+    /// Where each scanner is in the first scanner's frame.
+    pub fn positions(&self) -> Vec<Point> {
+        self.transforms.iter().map(|t| t.translation).collect()
+    }
+
+    /// Every distinct beacon, in the first scanner's frame.
+    pub fn beacons(&self, scanners: &[Scanner]) -> Vec<Point> {
+        let mut beacons: Vec<Point> = scanners
+            .iter()
+            .zip(&self.transforms)
+            .flat_map(|(s, t)| s.beacons.iter().map(|&p| t.apply(p)))
+            .collect();
+        beacons.sort_by_key(|p| p.coords());
+        beacons.dedup();
+        beacons
+    }
+}
@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+/// The most game states `count_wins` will track. Three players on the real
+/// board need just under ten million; four would need billions.
+const MAX_STATES: u64 = 1 << 24;
+
+/// The shape of a game: a circular track of `board` spaces numbered from 1,
+/// a die with `faces` sides rolled `rolls` times per turn, and the score a
+/// player needs to win.
+#[derive(Clone, Copy, Debug)]
+pub struct Rules {
+    pub board: usize,
+    pub faces: usize,
+    pub rolls: usize,
+    pub target: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RulesError {
+    NoPlayers,
+    /// A starting space that isn't on the board.
+    BadStart(usize),
+    /// Too many players for `count_wins` to track every game state.
+    TooManyPlayers(usize),
+    /// More universes than fit in a `u128`.
+    Overflow,
+}
+
+/// How a game with the deterministic die ended.
+#[derive(Clone, Debug)]
+pub struct Outcome {
+    pub winner: usize,
+    pub scores: Vec<usize>,
+    pub rolls: usize,
+}
+
+impl Outcome {
+    /// The lowest losing score times the number of rolls.
+    pub fn checksum(&self) -> usize {
+        let loser = (0..self.scores.len())
+            .filter(|&p| p != self.winner)
+            .map(|p| self.scores[p])
+            .min()
+            .unwrap_or(0);
+        loser * self.rolls
+    }
+}
+
+impl Rules {
+    /// The practice game, played with the deterministic 100-sided die.
+    pub fn practice() -> Self {
+        Self {
+            board: 10,
+            faces: 100,
+            rolls: 3,
+            target: 1000,
+        }
+    }
+
+    /// The real game, played with the three-sided Dirac die.
+    pub fn dirac() -> Self {
+        Self {
+            board: 10,
+            faces: 3,
+            rolls: 3,
+            target: 21,
+        }
+    }
+
+    /// Turns 1-based starting spaces into 0-based positions, checking every
+    /// one is on the board.
+    fn positions(&self, starts: &[usize]) -> Result<Vec<usize>, RulesError> {
+        if starts.is_empty() {
+            return Err(RulesError::NoPlayers);
+        }
+        starts
+            .iter()
+            .map(|&s| match s {
+                1.. if s <= self.board => Ok(s - 1),
+                _ => Err(RulesError::BadStart(s)),
+            })
+            .collect()
+    }
+
+    /// Moves `steps` spaces on from a 0-based position.
+    fn advance(&self, pos: usize, steps: usize) -> usize {
+        (pos + steps) % self.board
+    }
+
+    /// Plays with a die that rolls 1, 2, 3, ... wrapping after `faces`.
+    /// `starts` are the 1-based starting spaces, one per player.
+    pub fn play_deterministic(&self, starts: &[usize]) -> Result<Outcome, RulesError> {
+        let mut positions = self.positions(starts)?;
+        let mut scores = vec![0; starts.len()];
+        let mut rolls = 0;
+        let mut player = 0;
+
+        loop {
+            let steps: usize = (0..self.rolls).map(|i| (rolls + i) % self.faces + 1).sum();
+            rolls += self.rolls;
+            positions[player] = self.advance(positions[player], steps);
+            scores[player] += positions[player] + 1;
+            if scores[player] >= self.target {
+                return Ok(Outcome {
+                    winner: player,
+                    scores,
+                    rolls,
+                });
+            }
+            player = (player + 1) % starts.len();
+        }
+    }
+
+    /// Every total a turn's rolls can add up to, with the number of ways of
+    /// rolling it.
+    pub fn roll_sums(&self) -> Vec<(usize, usize)> {
+        let mut ways = vec![1];
+        for _ in 0..self.rolls {
+            let mut next = vec![0; ways.len() + self.faces];
+            for (sum, &n) in ways.iter().enumerate() {
+                for face in 1..=self.faces {
+                    next[sum + face] += n;
+                }
+            }
+            ways = next;
+        }
+        ways.into_iter()
+            .enumerate()
+            .filter(|&(_, n)| n > 0)
+            .collect()
+    }
+
+    /// In how many universes each player wins with the Dirac die.
+    ///
+    /// Works forward one turn at a time, keeping how many universes are in
+    /// each reachable combination of positions and scores, packed into a
+    /// `u64`. Games with more than `MAX_STATES` states are turned down.
+    /// Every universe ends within `players * target` turns, as each turn
+    /// scores at least one point.
+    pub fn count_wins(&self, starts: &[usize]) -> Result<Vec<u128>, RulesError> {
+        let players = starts.len();
+        let per_player = (self.board * self.target) as u64;
+        if per_player
+            .checked_pow(players as u32)
+            .is_none_or(|states| states > MAX_STATES)
+        {
+            return Err(RulesError::TooManyPlayers(players));
+        }
+        let sums = self.roll_sums();
+        let mut wins = vec![0u128; players];
+
+        let positions = self.positions(starts)?;
+        let start = self.encode(&positions, &vec![0; players]);
+        let mut current: HashMap<u64, u128> = HashMap::from([(start, 1)]);
+
+        for player in (0..players).cycle() {
+            if current.is_empty() {
+                break;
+            }
+            let mut next = HashMap::new();
+            for (index, universes) in current {
+                let (mut positions, mut scores) = self.decode(index, players);
+                let (pos, score) = (positions[player], scores[player]);
+                for &(steps, ways) in &sums {
+                    let count = universes
+                        .checked_mul(ways as u128)
+                        .ok_or(RulesError::Overflow)?;
+                    let moved = self.advance(pos, steps);
+                    let scored = score + moved + 1;
+                    let total = if scored >= self.target {
+                        &mut wins[player]
+                    } else {
+                        positions[player] = moved;
+                        scores[player] = scored;
+                        next.entry(self.encode(&positions, &scores)).or_insert(0)
+                    };
+                    *total = total.checked_add(count).ok_or(RulesError::Overflow)?;
+                }
+            }
+            current = next;
+        }
+
+        Ok(wins)
+    }
+
+    fn encode(&self, positions: &[usize], scores: &[usize]) -> u64 {
+        positions.iter().zip(scores).fold(0, |index, (&p, &s)| {
+            (index * self.board as u64 + p as u64) * self.target as u64 + s as u64
+        })
+    }
+
+    fn decode(&self, mut index: u64, players: usize) -> (Vec<usize>, Vec<usize>) {
+        let (board, target) = (self.board as u64, self.target as u64);
+        let mut positions = vec![0; players];
+        let mut scores = vec![0; players];
+        for p in (0..players).rev() {
+            scores[p] = (index % target) as usize;
+            index /= target;
+            positions[p] = (index % board) as usize;
+            index /= board;
+        }
+        (positions, scores)
+    }
+}
+
+/// Reads the 1-based starting space of each player.
+pub fn parse_starts(input: &str) -> Option<Vec<usize>> {
+    input
+        .lines()
+        .map(|l| l.rsplit_once(": ")?.1.trim().parse().ok())
+        .collect()
+}
@@ -1,6 +1,8 @@
 use std::io::{self, Read};
 use std::time::Instant;
 
+pub mod dirac;
+
 pub fn read_stdin() -> String {
     let mut buffer = String::new();
     let stdin = io::stdin();
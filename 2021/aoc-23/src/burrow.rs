@@ -0,0 +1,291 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::str::FromStr;
+
+/// Bits used per cell in a packed key: enough for empty plus seven kinds.
+const CELL_BITS: usize = 3;
+
+/// A state packed into a single integer, `CELL_BITS` per cell.
+pub type Key = u128;
+
+/// The layout of a burrow, read from the puzzle diagram.
+///
+/// Cells are numbered hallway first, left to right, then each room from top
+/// to bottom. A cell holds 0 when empty, or the amphipod's kind plus one,
+/// kinds being numbered by room from the left (`A` = 0).
+#[derive(Clone, Debug)]
+pub struct Burrow {
+    /// The x position of every hallway cell.
+    hallway: Vec<usize>,
+    /// The x position of every room, in kind order.
+    rooms: Vec<usize>,
+    pub depth: usize,
+    /// Energy per step for each kind.
+    pub costs: Vec<usize>,
+    /// The diagram with the amphipods cleared, for rendering.
+    template: Vec<String>,
+    start: Vec<u8>,
+}
+
+impl FromStr for Burrow {
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
+        let is_cell = |c: &char| *c == '.' || c.is_ascii_uppercase();
+        let cells_in =
+            |l: &[char]| -> Vec<usize> { (0..l.len()).filter(|&x| is_cell(&l[x])).collect() };
+
+        let hallway_y = lines.iter().position(|l| l.contains(&'.')).ok_or(())?;
+        let hallway = cells_in(&lines[hallway_y]);
+        let rooms = cells_in(lines.get(hallway_y + 1).ok_or(())?);
+        if rooms.is_empty() || rooms.iter().any(|x| !hallway.contains(x)) {
+            return Err(());
+        }
+
+        let room_lines = &lines[hallway_y + 1..];
+        let depth = room_lines
+            .iter()
+            .take_while(|l| rooms.iter().all(|&x| l.get(x).is_some_and(is_cell)))
+            .count();
+
+        let kind = |c: char| match c {
+            '.' => Ok(0),
+            c if c.is_ascii_uppercase() && ((c as u8 - b'A') as usize) < rooms.len() => {
+                Ok(c as u8 - b'A' + 1)
+            }
+            _ => Err(()),
+        };
+        let mut start = vec![];
+        for &x in &hallway {
+            start.push(kind(lines[hallway_y][x])?);
+        }
+        for &x in &rooms {
+            for l in &room_lines[..depth] {
+                start.push(kind(l[x])?);
+            }
+        }
+        if (hallway.len() + rooms.len() * depth) * CELL_BITS > Key::BITS as usize
+            || rooms.len() >= 1 << CELL_BITS
+        {
+            return Err(());
+        }
+
+        let template = lines
+            .iter()
+            .map(|l| {
+                l.iter()
+                    .map(|&c| if c.is_ascii_uppercase() { '.' } else { c })
+                    .collect()
+            })
+            .collect();
+
+        Ok(Self {
+            costs: (0..rooms.len() as u32).map(|k| 10usize.pow(k)).collect(),
+            hallway,
+            rooms,
+            depth,
+            template,
+            start,
+        })
+    }
+}
+
+impl Burrow {
+    fn room_cell(&self, room: usize, i: usize) -> usize {
+        self.hallway.len() + room * self.depth + i
+    }
+
+    fn hallway_index(&self, x: usize) -> usize {
+        self.hallway.iter().position(|&h| h == x).unwrap()
+    }
+
+    pub fn pack(&self, cells: &[u8]) -> Key {
+        cells
+            .iter()
+            .rev()
+            .fold(0, |key, &c| key << CELL_BITS | c as Key)
+    }
+
+    pub fn unpack(&self, mut key: Key) -> Vec<u8> {
+        (0..self.hallway.len() + self.rooms.len() * self.depth)
+            .map(|_| {
+                let c = (key & ((1 << CELL_BITS) - 1)) as u8;
+                key >>= CELL_BITS;
+                c
+            })
+            .collect()
+    }
+
+    pub fn start(&self) -> Key {
+        self.pack(&self.start)
+    }
+
+    /// Every room holds only its own kind, all the way down.
+    pub fn goal(&self) -> Key {
+        let mut cells = vec![0; self.hallway.len()];
+        for room in 0..self.rooms.len() {
+            cells.extend(std::iter::repeat_n(room as u8 + 1, self.depth));
+        }
+        self.pack(&cells)
+    }
+
+    /// Whether a room only holds amphipods that belong in it.
+    fn settled(&self, cells: &[u8], room: usize) -> bool {
+        (0..self.depth).all(|i| {
+            let c = cells[self.room_cell(room, i)];
+            c == 0 || c == room as u8 + 1
+        })
+    }
+
+    /// Whether the hallway is clear strictly between `from` and `to`, and at
+    /// `to` itself.
+    fn clear(&self, cells: &[u8], from: usize, to: usize) -> bool {
+        let (lo, hi) = (from.min(to), from.max(to));
+        self.hallway
+            .iter()
+            .enumerate()
+            .filter(|(_, &x)| x >= lo && x <= hi && x != from)
+            .all(|(i, _)| cells[i] == 0)
+    }
+
+    /// Moves an amphipod that has reached the top of a room (or is standing at
+    /// hallway position `x`) into its own room, if that is possible.
+    fn go_home(&self, cells: &[u8], kind: usize, x: usize) -> Option<(usize, usize)> {
+        let home = self.rooms[kind];
+        if !self.settled(cells, kind) || !self.clear(cells, x, home) {
+            return None;
+        }
+        let i = (0..self.depth)
+            .rev()
+            .find(|&i| cells[self.room_cell(kind, i)] == 0)?;
+        Some((self.room_cell(kind, i), x.abs_diff(home) + i + 1))
+    }
+
+    fn moves(&self, cells: &[u8]) -> Vec<(Vec<u8>, usize)> {
+        let mut moves = vec![];
+        let mut apply = |from: usize, to: usize, steps: usize| {
+            let mut next = cells.to_vec();
+            let kind = next[from] as usize - 1;
+            next.swap(from, to);
+            moves.push((next, steps * self.costs[kind]));
+        };
+
+        for (h, &x) in self.hallway.iter().enumerate() {
+            if cells[h] == 0 {
+                continue;
+            }
+            if let Some((to, steps)) = self.go_home(cells, cells[h] as usize - 1, x) {
+                apply(h, to, steps);
+            }
+        }
+
+        for (room, &rx) in self.rooms.iter().enumerate() {
+            if self.settled(cells, room) {
+                continue;
+            }
+            let Some(i) = (0..self.depth).find(|&i| cells[self.room_cell(room, i)] != 0) else {
+                continue;
+            };
+            let from = self.room_cell(room, i);
+            let kind = cells[from] as usize - 1;
+
+            if let Some((to, steps)) = self.go_home(cells, kind, rx) {
+                apply(from, to, steps + i + 1);
+                continue;
+            }
+
+            for &x in &self.hallway {
+                if self.rooms.contains(&x) || !self.clear(cells, rx, x) {
+                    continue;
+                }
+                apply(from, self.hallway_index(x), i + 1 + rx.abs_diff(x));
+            }
+        }
+
+        moves
+    }
+
+    /// A lower bound on the energy still needed: every amphipod in the
+    /// hallway has to walk into its room, and every amphipod sitting on top of
+    /// a stranger has to climb out, cross over and step down into its room.
+    fn h(&self, cells: &[u8]) -> usize {
+        let mut total = 0;
+        for (h, &x) in self.hallway.iter().enumerate() {
+            if cells[h] != 0 {
+                let kind = cells[h] as usize - 1;
+                total += (x.abs_diff(self.rooms[kind]) + 1) * self.costs[kind];
+            }
+        }
+        for (room, &rx) in self.rooms.iter().enumerate() {
+            let cell = |i| cells[self.room_cell(room, i)];
+            let Some(deepest) = (0..self.depth)
+                .rev()
+                .find(|&i| cell(i) != 0 && cell(i) != room as u8 + 1)
+            else {
+                continue;
+            };
+            for i in (0..=deepest).filter(|&i| cell(i) != 0) {
+                let kind = cell(i) as usize - 1;
+                let across = rx.abs_diff(self.rooms[kind]).max(2);
+                total += (i + 1 + across + 1) * self.costs[kind];
+            }
+        }
+        total
+    }
+
+    /// The least energy needed to organise the amphipods, found with A*.
+    pub fn solve(&self) -> Option<usize> {
+        let goal = self.goal();
+        let start = self.start();
+        let mut g: HashMap<Key, usize> = HashMap::from([(start, 0)]);
+        let mut open = BinaryHeap::from([(Reverse(self.h(&self.start)), 0, start)]);
+
+        while let Some((_, cost, key)) = open.pop() {
+            if key == goal {
+                return Some(cost);
+            }
+            if g.get(&key).is_some_and(|&best| best < cost) {
+                continue;
+            }
+
+            let cells = self.unpack(key);
+            for (next, step) in self.moves(&cells) {
+                let next_cost = cost + step;
+                let next_key = self.pack(&next);
+                if g.get(&next_key).is_none_or(|&best| next_cost < best) {
+                    g.insert(next_key, next_cost);
+                    open.push((Reverse(next_cost + self.h(&next)), next_cost, next_key));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Draws a state in the shape of the original diagram.
+    pub fn render(&self, key: Key) -> String {
+        let cells = self.unpack(key);
+        let hallway_y = self.template.iter().position(|l| l.contains('.')).unwrap();
+        let mut lines: Vec<Vec<char>> = self.template.iter().map(|l| l.chars().collect()).collect();
+
+        let mut draw = |x: usize, y: usize, c: u8| {
+            if c != 0 {
+                lines[y][x] = (b'A' + c - 1) as char;
+            }
+        };
+        for (h, &x) in self.hallway.iter().enumerate() {
+            draw(x, hallway_y, cells[h]);
+        }
+        for (room, &x) in self.rooms.iter().enumerate() {
+            for i in 0..self.depth {
+                draw(x, hallway_y + 1 + i, cells[self.room_cell(room, i)]);
+            }
+        }
+
+        lines
+            .into_iter()
+            .map(|l| l.into_iter().collect::<String>() + "\n")
+            .collect()
+    }
+}
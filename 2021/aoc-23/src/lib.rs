@@ -2,6 +2,8 @@ use std::io::{self, Read};
 use std::time::Instant;
 use std::fmt;
 
+pub mod burrow;
+
 pub fn read_stdin() -> String {
     let mut buffer = String::new();
     let stdin = io::stdin();
@@ -0,0 +1,314 @@
+use std::str::FromStr;
+
+/// The right hand side of an `Operation: new = ...` line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Old,
+    Lit(u64),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MonkeyError {
+    BadLine(String),
+    BadExpr(String),
+    MissingField(usize, &'static str),
+    /// A monkey throws to a monkey that doesn't exist.
+    UnknownMonkey(usize, usize),
+    /// A monkey throws to itself, so its turn would never end.
+    ThrowsToItself(usize),
+    /// A worry level went out of range while being worked out exactly.
+    Overflow(usize),
+}
+
+impl FromStr for Expr {
+    type Err = MonkeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bad = || MonkeyError::BadExpr(s.into());
+        let mut tokens = vec![];
+        let mut rest = s.trim_start();
+        while let Some(c) = rest.chars().next() {
+            let len = if c.is_ascii_alphanumeric() {
+                rest.find(|c: char| !c.is_ascii_alphanumeric())
+                    .unwrap_or(rest.len())
+            } else {
+                c.len_utf8()
+            };
+            tokens.push(&rest[..len]);
+            rest = rest[len..].trim_start();
+        }
+
+        let mut parser = Parser { tokens, at: 0 };
+        let expr = parser.sum().ok_or_else(bad)?;
+        if parser.at != parser.tokens.len() {
+            return Err(bad());
+        }
+        Ok(expr)
+    }
+}
+
+/// Recursive descent over the tokens, with `*` binding tighter than `+`
+/// and `-`, and everything associating to the left.
+struct Parser<'a> {
+    tokens: Vec<&'a str>,
+    at: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn next(&mut self) -> Option<&'a str> {
+        let token = *self.tokens.get(self.at)?;
+        self.at += 1;
+        Some(token)
+    }
+
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.at).copied()
+    }
+
+    fn sum(&mut self) -> Option<Expr> {
+        let mut expr = self.product()?;
+        while let Some(op @ ("+" | "-")) = self.peek() {
+            self.at += 1;
+            let right = Box::new(self.product()?);
+            expr = match op {
+                "+" => Expr::Add(Box::new(expr), right),
+                _ => Expr::Sub(Box::new(expr), right),
+            };
+        }
+        Some(expr)
+    }
+
+    fn product(&mut self) -> Option<Expr> {
+        let mut expr = self.atom()?;
+        while self.peek() == Some("*") {
+            self.at += 1;
+            expr = Expr::Mul(Box::new(expr), Box::new(self.atom()?));
+        }
+        Some(expr)
+    }
+
+    fn atom(&mut self) -> Option<Expr> {
+        match self.next()? {
+            "old" => Some(Expr::Old),
+            "(" => {
+                let expr = self.sum()?;
+                (self.next()? == ")").then_some(expr)
+            }
+            n => n.parse().ok().map(Expr::Lit),
+        }
+    }
+}
+
+impl Expr {
+    /// The new worry level, exactly, or `None` if it doesn't fit in a `u64`.
+    pub fn eval(&self, old: u64) -> Option<u64> {
+        match self {
+            Expr::Old => Some(old),
+            Expr::Lit(n) => Some(*n),
+            Expr::Add(a, b) => a.eval(old)?.checked_add(b.eval(old)?),
+            Expr::Sub(a, b) => a.eval(old)?.checked_sub(b.eval(old)?),
+            Expr::Mul(a, b) => a.eval(old)?.checked_mul(b.eval(old)?),
+        }
+    }
+
+    /// The new worry level modulo `modulus`, given `old` modulo `modulus`.
+    /// Every step is reduced, so this never overflows.
+    pub fn eval_mod(&self, old: u64, modulus: u64) -> u64 {
+        let m = modulus as u128;
+        let eval = |e: &Expr| e.eval_mod(old, modulus) as u128;
+        let value = match self {
+            Expr::Old => old as u128,
+            Expr::Lit(n) => *n as u128,
+            Expr::Add(a, b) => eval(a) + eval(b),
+            Expr::Sub(a, b) => eval(a) + m - eval(b),
+            Expr::Mul(a, b) => eval(a) * eval(b),
+        };
+        (value % m) as u64
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Monkey {
+    pub items: Vec<u64>,
+    pub operation: Expr,
+    pub divisor: u64,
+    pub if_true: usize,
+    pub if_false: usize,
+}
+
+impl Monkey {
+    fn throw_to(&self, worry: u64) -> usize {
+        if worry.is_multiple_of(self.divisor) {
+            self.if_true
+        } else {
+            self.if_false
+        }
+    }
+}
+
+/// Parses every monkey's notes. Monkeys have to be listed in order, but the
+/// fields within a monkey can come in any order.
+pub fn parse(input: &str) -> Result<Vec<Monkey>, MonkeyError> {
+    let monkeys = input
+        .split("\n\n")
+        .filter(|block| !block.trim().is_empty())
+        .enumerate()
+        .map(|(id, block)| parse_monkey(id, block))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (id, monkey) in monkeys.iter().enumerate() {
+        for to in [monkey.if_true, monkey.if_false] {
+            if to == id {
+                return Err(MonkeyError::ThrowsToItself(id));
+            }
+            if to >= monkeys.len() {
+                return Err(MonkeyError::UnknownMonkey(id, to));
+            }
+        }
+    }
+    Ok(monkeys)
+}
+
+fn parse_monkey(id: usize, block: &str) -> Result<Monkey, MonkeyError> {
+    let mut lines = block.lines().map(str::trim).filter(|l| !l.is_empty());
+    let header = lines.next().unwrap_or_default();
+    if header != format!("Monkey {}:", id) {
+        return Err(MonkeyError::BadLine(header.into()));
+    }
+
+    let (mut items, mut operation, mut divisor, mut if_true, mut if_false) =
+        (None, None, None, None, None);
+    for line in lines {
+        let bad = || MonkeyError::BadLine(line.into());
+        let number = |n: &str| n.trim().parse().map_err(|_| bad());
+        let (field, value) = line.split_once(':').ok_or_else(bad)?;
+        let value = value.trim();
+        match field {
+            "Starting items" => {
+                items = Some(
+                    value
+                        .split(',')
+                        .filter(|v| !v.trim().is_empty())
+                        .map(number)
+                        .collect::<Result<_, _>>()?,
+                )
+            }
+            "Operation" => {
+                let expr = value.strip_prefix("new =").ok_or_else(bad)?;
+                operation = Some(expr.parse()?);
+            }
+            "Test" => {
+                let n = number(value.strip_prefix("divisible by").ok_or_else(bad)?)?;
+                if n == 0 {
+                    return Err(bad());
+                }
+                divisor = Some(n);
+            }
+            "If true" | "If false" => {
+                let to = number(value.strip_prefix("throw to monkey").ok_or_else(bad)?)?;
+                if field == "If true" {
+                    if_true = Some(to as usize);
+                } else {
+                    if_false = Some(to as usize);
+                }
+            }
+            _ => return Err(bad()),
+        }
+    }
+
+    let missing = |field| MonkeyError::MissingField(id, field);
+    Ok(Monkey {
+        items: items.ok_or_else(|| missing("Starting items"))?,
+        operation: operation.ok_or_else(|| missing("Operation"))?,
+        divisor: divisor.ok_or_else(|| missing("Test"))?,
+        if_true: if_true.ok_or_else(|| missing("If true"))?,
+        if_false: if_false.ok_or_else(|| missing("If false"))?,
+    })
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// The monkeys passing items around, round by round.
+#[derive(Debug, Clone)]
+pub struct Simulation {
+    pub monkeys: Vec<Monkey>,
+    /// Worry is divided by this after each inspection.
+    pub relief: u64,
+    /// With no relief, worry only matters modulo every divisor, so it's
+    /// kept modulo their lowest common multiple.
+    modulus: Option<u64>,
+    /// How many items each monkey has inspected so far.
+    pub inspections: Vec<u64>,
+}
+
+impl Simulation {
+    pub fn new(monkeys: Vec<Monkey>, relief: u64) -> Self {
+        assert!(relief > 0, "Relief has to divide worry by something");
+        let modulus = (relief == 1).then(|| {
+            monkeys
+                .iter()
+                .fold(1, |m, monkey| m / gcd(m, monkey.divisor) * monkey.divisor)
+        });
+        let mut simulation = Self {
+            inspections: vec![0; monkeys.len()],
+            monkeys,
+            relief,
+            modulus,
+        };
+        if let Some(m) = modulus {
+            for monkey in &mut simulation.monkeys {
+                monkey.items.iter_mut().for_each(|i| *i %= m);
+            }
+        }
+        simulation
+    }
+
+    /// Plays one round, giving how many items each monkey inspected in it.
+    pub fn round(&mut self) -> Result<Vec<u64>, MonkeyError> {
+        let mut counts = Vec::with_capacity(self.monkeys.len());
+        for id in 0..self.monkeys.len() {
+            let items = std::mem::take(&mut self.monkeys[id].items);
+            counts.push(items.len() as u64);
+            for old in items {
+                let monkey = &self.monkeys[id];
+                let worry = match self.modulus {
+                    Some(m) => monkey.operation.eval_mod(old, m),
+                    None => {
+                        monkey
+                            .operation
+                            .eval(old)
+                            .ok_or(MonkeyError::Overflow(id))?
+                            / self.relief
+                    }
+                };
+                let to = monkey.throw_to(worry);
+                self.monkeys[to].items.push(worry);
+            }
+        }
+        for (total, count) in self.inspections.iter_mut().zip(&counts) {
+            *total += count;
+        }
+        Ok(counts)
+    }
+
+    /// Plays `rounds` rounds, giving each round's inspection counts in turn.
+    pub fn run(&mut self, rounds: usize) -> Result<Vec<Vec<u64>>, MonkeyError> {
+        (0..rounds).map(|_| self.round()).collect()
+    }
+
+    /// The product of the two highest inspection totals so far.
+    pub fn monkey_business(&self) -> u64 {
+        let mut totals = self.inspections.clone();
+        totals.sort_unstable_by(|a, b| b.cmp(a));
+        totals.iter().take(2).product()
+    }
+}
@@ -0,0 +1,259 @@
+pub type Point = (isize, isize);
+
+/// Where sand comes from in the puzzle.
+pub const SOURCE: Point = (500, 0);
+
+/// What's under the cave.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Floor {
+    /// Nothing: sand falling past the lowest rock is lost.
+    Abyss,
+    /// A floor two below the lowest rock, going on forever sideways.
+    Infinite,
+    /// A floor at this depth, going on forever sideways.
+    At(isize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CaveError {
+    BadPoint(String),
+    /// Rock paths only go straight across or straight down.
+    Diagonal(Point, Point),
+    /// The floor is at or above the source.
+    FloorTooHigh(isize),
+}
+
+/// Parses rock paths like `498,4 -> 498,6 -> 496,6`.
+pub fn parse_paths(input: &str) -> Result<Vec<Vec<Point>>, CaveError> {
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(|line| {
+            line.split("->")
+                .map(|point| {
+                    let bad = || CaveError::BadPoint(point.trim().into());
+                    let (x, y) = point.trim().split_once(',').ok_or_else(bad)?;
+                    Ok((x.parse().map_err(|_| bad())?, y.parse().map_err(|_| bad())?))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    Air,
+    Rock,
+    Sand,
+}
+
+/// What one step of the simulation did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    /// The falling grain moved here, or a new grain appeared here.
+    Moved(Point),
+    /// The falling grain came to rest here.
+    Rested(Point),
+    /// The falling grain fell into the abyss.
+    Lost,
+    /// Sand has piled up to the source, so no more can come out.
+    Blocked,
+}
+
+/// The cave as a dense grid, wide enough that sand never falls off the
+/// sides of a floor. Coordinates can go negative, so the grid can spread
+/// as far left of the source as it needs to.
+///
+/// Grains are dropped along a remembered path: each grain follows the path
+/// of the one before it up to where that one came to rest, so the next
+/// grain starts from the last point on the path rather than the source.
+#[derive(Debug, Clone)]
+pub struct Cave {
+    cells: Vec<Cell>,
+    min_x: isize,
+    min_y: isize,
+    width: usize,
+    /// The first row below the grid. Sand reaching it either hits the floor
+    /// or is lost.
+    bottom: isize,
+    source: Point,
+    floor: Floor,
+    /// The current grain's path from the source, with the grain at the end.
+    path: Vec<Point>,
+    done: Option<Step>,
+}
+
+impl Cave {
+    pub fn new(paths: &[Vec<Point>], source: Point, floor: Floor) -> Result<Self, CaveError> {
+        for path in paths {
+            for pair in path.windows(2) {
+                if pair[0].0 != pair[1].0 && pair[0].1 != pair[1].1 {
+                    return Err(CaveError::Diagonal(pair[0], pair[1]));
+                }
+            }
+        }
+
+        let points = || paths.iter().flatten().chain([&source]);
+        let lowest = points().map(|p| p.1).max().unwrap();
+        let min_y = points().map(|p| p.1).min().unwrap();
+        let bottom = match floor {
+            Floor::Abyss => lowest + 1,
+            Floor::Infinite => lowest + 2,
+            Floor::At(y) if y <= source.1 => return Err(CaveError::FloorTooHigh(y)),
+            Floor::At(y) => y,
+        };
+        // Sand spreads at most one column either way for each row it falls.
+        let spread = bottom - source.1;
+        let min_x = points()
+            .map(|p| p.0)
+            .chain([source.0 - spread])
+            .min()
+            .unwrap()
+            - 1;
+        let max_x = points()
+            .map(|p| p.0)
+            .chain([source.0 + spread])
+            .max()
+            .unwrap()
+            + 1;
+        let width = (max_x - min_x + 1) as usize;
+
+        let mut cave = Self {
+            cells: vec![Cell::Air; width * (bottom - min_y) as usize],
+            min_x,
+            min_y,
+            width,
+            bottom,
+            source,
+            floor,
+            path: vec![],
+            done: None,
+        };
+        for path in paths {
+            for pair in path.windows(2) {
+                let (xs, ys) = ((pair[0].0, pair[1].0), (pair[0].1, pair[1].1));
+                for x in xs.0.min(xs.1)..=xs.0.max(xs.1) {
+                    for y in ys.0.min(ys.1)..=ys.0.max(ys.1) {
+                        cave.set((x, y), Cell::Rock);
+                    }
+                }
+            }
+            if let [point] = path[..] {
+                cave.set(point, Cell::Rock);
+            }
+        }
+        Ok(cave)
+    }
+
+    fn index(&self, (x, y): Point) -> Option<usize> {
+        let (column, row) = (x - self.min_x, y - self.min_y);
+        (column >= 0 && (column as usize) < self.width && row >= 0 && y < self.bottom)
+            .then(|| row as usize * self.width + column as usize)
+    }
+
+    /// What's at `point`. Anything below the grid is the floor, if there is
+    /// one, or the abyss.
+    pub fn get(&self, point: Point) -> Cell {
+        match self.index(point) {
+            Some(i) => self.cells[i],
+            None if self.floor != Floor::Abyss && point.1 >= self.bottom => Cell::Rock,
+            None => Cell::Air,
+        }
+    }
+
+    fn set(&mut self, point: Point, cell: Cell) {
+        if let Some(i) = self.index(point) {
+            self.cells[i] = cell;
+        }
+    }
+
+    /// Moves the falling grain one step, dropping a new grain first if
+    /// there isn't one.
+    pub fn step(&mut self) -> Step {
+        if let Some(done) = self.done {
+            return done;
+        }
+        let Some(&(x, y)) = self.path.last() else {
+            if self.get(self.source) != Cell::Air {
+                self.done = Some(Step::Blocked);
+                return Step::Blocked;
+            }
+            self.path.push(self.source);
+            return Step::Moved(self.source);
+        };
+
+        let below = [(x, y + 1), (x - 1, y + 1), (x + 1, y + 1)];
+        match below.into_iter().find(|&p| self.get(p) == Cell::Air) {
+            Some(next) if self.index(next).is_none() => {
+                self.done = Some(Step::Lost);
+                Step::Lost
+            }
+            Some(next) => {
+                self.path.push(next);
+                Step::Moved(next)
+            }
+            None => {
+                self.set((x, y), Cell::Sand);
+                self.path.pop();
+                Step::Rested((x, y))
+            }
+        }
+    }
+
+    /// Drops grains until one comes to rest, giving where, or `None` once
+    /// sand is being lost or the source is blocked.
+    pub fn drop_grain(&mut self) -> Option<Point> {
+        loop {
+            match self.step() {
+                Step::Moved(_) => (),
+                Step::Rested(point) => return Some(point),
+                Step::Lost | Step::Blocked => return None,
+            }
+        }
+    }
+
+    /// Drops grains until no more come to rest, giving how many did.
+    pub fn fill(&mut self) -> usize {
+        std::iter::from_fn(|| self.drop_grain()).count()
+    }
+
+    /// The falling grain, if there is one.
+    pub fn grain(&self) -> Option<Point> {
+        self.path.last().copied()
+    }
+
+    /// Draws the part of the cave with anything in it: `#` for rock, `o`
+    /// for resting sand, `~` for the falling grain and `+` for the source.
+    pub fn render(&self) -> String {
+        let filled = (self.min_y..self.bottom).flat_map(|y| {
+            (self.min_x..self.min_x + self.width as isize)
+                .map(move |x| (x, y))
+                .filter(|&p| self.get(p) != Cell::Air)
+        });
+        let points: Vec<Point> = filled.chain([self.source]).chain(self.grain()).collect();
+        let min_x = points.iter().map(|p| p.0).min().unwrap();
+        let min_y = points.iter().map(|p| p.1).min().unwrap();
+        let max_x = points.iter().map(|p| p.0).max().unwrap();
+        let max_y = points.iter().map(|p| p.1).max().unwrap();
+        let bottom = match self.floor {
+            Floor::Abyss => max_y,
+            _ => self.bottom,
+        };
+
+        let mut out = String::new();
+        for y in min_y..=bottom {
+            for x in min_x..=max_x {
+                out.push(match self.get((x, y)) {
+                    _ if Some((x, y)) == self.grain() => '~',
+                    Cell::Rock => '#',
+                    Cell::Sand => 'o',
+                    Cell::Air if (x, y) == self.source => '+',
+                    Cell::Air => '.',
+                });
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
@@ -2,6 +2,7 @@ use std::io::{self, Read};
 
 pub mod intset;
 pub mod graph_fns;
+pub mod schedule;
 
 pub fn read_stdin() -> String {
     let mut buffer = String::new();
@@ -1,17 +1,11 @@
 use aoc_16::{
     graph_fns::{dijkstra, Node},
-    intset::IntSet,
     read_stdin,
+    schedule::Valves,
 };
 
 use fxhash::FxHashMap;
-use lazy_static::lazy_static;
-use rayon::prelude::*;
-use std::{sync::Mutex, time::Instant};
-
-lazy_static! {
-    static ref CACHE: Mutex<FxHashMap<State, usize>> = Mutex::new(FxHashMap::default());
-}
+use std::time::Instant;
 
 type Id = u8;
 
@@ -121,99 +115,36 @@ fn key(a: &Id, b: &Id) -> (Id, Id) {
     (*a.min(b), *a.max(b))
 }
 
-#[derive(Clone, Eq, PartialEq, Hash)]
-struct State {
-    remaining: IntSet,
-    time_left: u8,
-    current: Id,
-}
+fn valves(input: &Input) -> Valves {
+    let mut ids: Vec<Id> = input.rates.keys().copied().filter(|&id| id != 0).collect();
+    ids.sort();
 
-impl State {
-    fn new(remaining: IntSet, time_left: u8, current: Id) -> State {
-        State {
-            remaining,
-            time_left,
-            current,
-        }
-    }
-
-    fn neighbours(
-        &self,
-        rates: &FxHashMap<Id, u8>,
-        shortest_paths: &FxHashMap<(Id, Id), u8>,
-    ) -> Vec<(Self, usize)> {
-        let mut neighbours = vec![];
-
-        for id in self.remaining.items() {
-            let mut remaining = self.remaining;
-            remaining.remove(id);
-
-            let distance_to = shortest_paths[&key(&id, &self.current)];
-            if distance_to < self.time_left {
-                let new_time_left = self.time_left - distance_to - 1;
-                neighbours.push((
-                    State::new(remaining, new_time_left, id),
-                    (new_time_left as usize * rates[&id] as usize),
-                ))
-            }
-        }
-
-        neighbours
-    }
-}
-
-fn search(
-    state: State,
-    rates: &FxHashMap<Id, u8>,
-    shortest_paths: &FxHashMap<(Id, Id), u8>,
-) -> usize {
-    {
-        let x = CACHE.lock().unwrap();
-        if x.contains_key(&state) {
-            return x[&state];
-        }
-    }
-
-    let v = state
-        .neighbours(rates, shortest_paths)
-        .into_par_iter()
-        .map(|(new_state, vented)| vented + search(new_state, rates, shortest_paths))
-        .max()
-        .unwrap_or(0);
-
-    {
-        CACHE.lock().unwrap().insert(state, v);
-    }
+    let rates = ids.iter().map(|id| input.rates[id] as usize).collect();
+    let distances = ids
+        .iter()
+        .chain(std::iter::once(&0))
+        .map(|a| {
+            ids.iter()
+                .map(|b| {
+                    if a == b {
+                        0
+                    } else {
+                        input.shortest_paths[&key(a, b)] as usize
+                    }
+                })
+                .collect()
+        })
+        .collect();
 
-    v
+    Valves::new(rates, distances)
 }
 
 fn part_1(input: Input) -> usize {
-    let mut remaining = IntSet::new(&input.rates.keys().copied().collect());
-    remaining.remove(0);
-
-    let s = State::new(remaining, 30, 0);
-    search(s, &input.rates, &input.shortest_paths)
+    valves(&input).max_pressure(30, 1)
 }
 
 fn part_2(input: Input) -> usize {
-    let mut remaining = IntSet::new(&input.rates.keys().copied().collect());
-    remaining.remove(0);
-
-    (1..remaining.v)
-        .filter(|v| v % 2 != 1)
-        .par_bridge()
-        .map(|v| (IntSet::new_with(v), IntSet::new_with(remaining.v - v)))
-        .map(|(me, elephant)| {
-            search(State::new(me, 26, 0), &input.rates, &input.shortest_paths)
-                + search(
-                    State::new(elephant, 26, 0),
-                    &input.rates,
-                    &input.shortest_paths,
-                )
-        })
-        .max()
-        .unwrap()
+    valves(&input).max_pressure(26, 2)
 }
 
 fn main() {
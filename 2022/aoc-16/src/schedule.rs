@@ -0,0 +1,113 @@
+/// The valves worth opening, with travel times between them already
+/// collapsed to shortest paths. Valve `i` is bit `i` of a subset mask; the
+/// start is an extra row at the end of `distances`, since nothing travels
+/// back to it.
+pub struct Valves {
+    rates: Vec<usize>,
+    distances: Vec<Vec<usize>>,
+}
+
+impl Valves {
+    /// `distances` must have one row per rate plus a final row for the
+    /// starting position, and one column per rate.
+    pub fn new(rates: Vec<usize>, distances: Vec<Vec<usize>>) -> Self {
+        assert_eq!(distances.len(), rates.len() + 1);
+        assert!(distances.iter().all(|row| row.len() == rates.len()));
+        assert!(rates.len() < usize::BITS as usize);
+        Self { rates, distances }
+    }
+
+    fn start(&self) -> usize {
+        self.rates.len()
+    }
+
+    fn full(&self) -> usize {
+        (1 << self.rates.len()) - 1
+    }
+
+    /// The most pressure one agent can release in `time` minutes when only
+    /// allowed to open valves within each subset.
+    pub fn best_per_subset(&self, time: usize) -> Vec<usize> {
+        let mut best = vec![0; self.full() + 1];
+        self.visit(self.start(), time, 0, 0, &mut best);
+
+        // Opening exactly a subset is at most as good as being allowed to open
+        // anything within it.
+        for bit in 0..self.rates.len() {
+            for mask in 0..best.len() {
+                if mask & 1 << bit != 0 {
+                    best[mask] = best[mask].max(best[mask ^ 1 << bit]);
+                }
+            }
+        }
+        best
+    }
+
+    fn visit(
+        &self,
+        at: usize,
+        time_left: usize,
+        opened: usize,
+        released: usize,
+        best: &mut [usize],
+    ) {
+        best[opened] = best[opened].max(released);
+        for next in 0..self.rates.len() {
+            if opened & 1 << next != 0 {
+                continue;
+            }
+            let cost = self.distances[at][next] + 1;
+            if cost < time_left {
+                let time_left = time_left - cost;
+                self.visit(
+                    next,
+                    time_left,
+                    opened | 1 << next,
+                    released + time_left * self.rates[next],
+                    best,
+                );
+            }
+        }
+    }
+
+    /// The most pressure `agents` agents working in parallel can release in
+    /// `time` minutes. Agents never benefit from opening the same valve, so
+    /// this is the best split of the valves into disjoint subsets.
+    pub fn max_pressure(&self, time: usize, agents: usize) -> usize {
+        let full = self.full();
+        if agents == 0 {
+            return 0;
+        }
+
+        let single = self.best_per_subset(time);
+        let mut combined = single.clone();
+        for _ in 1..agents - 1 {
+            combined = (0..=full)
+                .map(|mask| {
+                    submasks(mask)
+                        .map(|s| single[s] + combined[mask ^ s])
+                        .max()
+                        .unwrap_or(0)
+                })
+                .collect();
+        }
+
+        if agents == 1 {
+            return single[full];
+        }
+        submasks(full)
+            .map(|s| single[s] + combined[full ^ s])
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Every subset of `mask`, including itself and the empty set.
+fn submasks(mask: usize) -> impl Iterator<Item = usize> {
+    let mut next = Some(mask);
+    std::iter::from_fn(move || {
+        let s = next?;
+        next = if s == 0 { None } else { Some((s - 1) & mask) };
+        Some(s)
+    })
+}
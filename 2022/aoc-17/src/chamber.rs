@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+
+/// The rocks from the puzzle, top row first, in the order they fall.
+pub const ROCKS: [&[&str]; 5] = [
+    &["####"],
+    &[".#.", "###", ".#."],
+    &["..#", "..#", "###"],
+    &["#", "#", "#", "#"],
+    &["##", "##"],
+];
+
+/// How many rows from the top of the tower make up a cycle detection key.
+const KEY_DEPTH: usize = 32;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Jet {
+    L,
+    R,
+}
+
+pub fn parse_jets(input: &str) -> Vec<Jet> {
+    input
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '>' => Jet::R,
+            '<' => Jet::L,
+            _ => panic!("Unknown character {}", c),
+        })
+        .collect()
+}
+
+/// A rock as one bitmask per row, bottom row first, with bit `x` set for
+/// column `x` from its left edge.
+#[derive(Clone, Debug)]
+pub struct Shape {
+    rows: Vec<u8>,
+    width: usize,
+}
+
+impl Shape {
+    /// Builds a shape from rows of `#`/`.`, written top row first.
+    pub fn parse(rows: &[&str]) -> Self {
+        let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        assert!(width <= 8, "Shapes can be at most 8 wide");
+        let rows = rows
+            .iter()
+            .rev()
+            .map(|r| {
+                r.chars()
+                    .enumerate()
+                    .filter(|(_, c)| *c == '#')
+                    .fold(0, |mask, (x, _)| mask | 1 << x)
+            })
+            .collect();
+        Self { rows, width }
+    }
+
+    fn at(&self, x: usize) -> impl Iterator<Item = u8> + '_ {
+        self.rows.iter().map(move |r| r << x)
+    }
+}
+
+/// Everything that decides how the rest of the tower grows: the next rock,
+/// the next jet and the shape of the top of the tower.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct StateKey {
+    pub rock: usize,
+    pub jet: usize,
+    pub top: Vec<u8>,
+}
+
+pub struct Chamber {
+    width: usize,
+    shapes: Vec<Shape>,
+    jets: Vec<Jet>,
+    rows: Vec<u8>,
+    next_jet: usize,
+    dropped: usize,
+}
+
+impl Chamber {
+    pub fn new(width: usize, shapes: Vec<Shape>, jets: Vec<Jet>) -> Self {
+        assert!(width <= 8, "Rows are stored as u8 masks");
+        assert!(shapes.iter().all(|s| s.width <= width));
+        Self {
+            width,
+            shapes,
+            jets,
+            rows: vec![],
+            next_jet: 0,
+            dropped: 0,
+        }
+    }
+
+    /// The puzzle's chamber: seven wide, with the five standard rocks.
+    pub fn standard(jets: Vec<Jet>) -> Self {
+        Self::new(7, ROCKS.iter().map(|r| Shape::parse(r)).collect(), jets)
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn collides(&self, shape: &Shape, x: usize, y: usize) -> bool {
+        shape
+            .at(x)
+            .zip(self.rows.iter().skip(y))
+            .any(|(s, r)| s & r != 0)
+    }
+
+    /// Drops the next rock until it comes to rest.
+    pub fn drop_rock(&mut self) {
+        let shape = &self.shapes[self.dropped % self.shapes.len()];
+        let mut x: usize = 2;
+        let mut y = self.rows.len() + 3;
+
+        loop {
+            let jet = self.jets[self.next_jet];
+            self.next_jet = (self.next_jet + 1) % self.jets.len();
+            let nx = match jet {
+                Jet::L => x.checked_sub(1),
+                Jet::R => Some(x + 1).filter(|nx| nx + shape.width <= self.width),
+            };
+            if let Some(nx) = nx.filter(|&nx| !self.collides(shape, nx, y)) {
+                x = nx;
+            }
+
+            if y == 0 || self.collides(shape, x, y - 1) {
+                break;
+            }
+            y -= 1;
+        }
+
+        let top = y + shape.rows.len();
+        if self.rows.len() < top {
+            self.rows.resize(top, 0);
+        }
+        for (r, s) in self.rows[y..].iter_mut().zip(shape.at(x)) {
+            *r |= s;
+        }
+        self.dropped += 1;
+    }
+
+    pub fn state_key(&self, depth: usize) -> StateKey {
+        StateKey {
+            rock: self.dropped % self.shapes.len(),
+            jet: self.next_jet,
+            top: self.rows.iter().rev().take(depth).copied().collect(),
+        }
+    }
+
+    /// The height of the tower once `rocks` rocks in total have fallen.
+    ///
+    /// As soon as a state repeats, the rocks and height gained between the
+    /// two sightings are repeated as many whole times as fit, and only the
+    /// remainder is actually simulated.
+    pub fn height_after(&mut self, rocks: usize) -> usize {
+        let mut seen: HashMap<StateKey, (usize, usize)> = HashMap::new();
+        let mut skipped = None;
+        while self.dropped < rocks {
+            self.drop_rock();
+            if skipped.is_some() {
+                continue;
+            }
+
+            let key = self.state_key(KEY_DEPTH);
+            if let Some(&(dropped, height)) = seen.get(&key) {
+                let period = self.dropped - dropped;
+                let cycles = (rocks - self.dropped) / period;
+                self.dropped += cycles * period;
+                skipped = Some(cycles * (self.height() - height));
+            } else {
+                seen.insert(key, (self.dropped, self.height()));
+            }
+        }
+        self.height() + skipped.unwrap_or(0)
+    }
+
+    /// Renders the top `n` rows of the tower, including the floor if it is in
+    /// view.
+    pub fn render(&self, n: usize) -> String {
+        let mut out = String::new();
+        for row in self.rows.iter().rev().take(n) {
+            out.push('|');
+            out.extend((0..self.width).map(|x| if row >> x & 1 == 1 { '#' } else { '.' }));
+            out.push_str("|\n");
+        }
+        if n >= self.rows.len() {
+            out.push('+');
+            out.push_str(&"-".repeat(self.width));
+            out.push_str("+\n");
+        }
+        out
+    }
+}
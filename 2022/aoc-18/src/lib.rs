@@ -1,5 +1,7 @@
 use std::io::{self, Read};
 
+pub mod voxels;
+
 pub fn read_stdin() -> String {
     let mut buffer = String::new();
     let stdin = io::stdin();
@@ -0,0 +1,219 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, PartialEq, PartialOrd, Ord, Eq, Hash, Debug)]
+pub struct Voxel {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl Voxel {
+    pub fn new(x: i64, y: i64, z: i64) -> Self {
+        Self { x, y, z }
+    }
+
+    /// The six voxels sharing a face with this one.
+    pub fn neighbours(&self) -> [Voxel; 6] {
+        let &Voxel { x, y, z } = self;
+        [
+            Voxel::new(x - 1, y, z),
+            Voxel::new(x + 1, y, z),
+            Voxel::new(x, y - 1, z),
+            Voxel::new(x, y + 1, z),
+            Voxel::new(x, y, z - 1),
+            Voxel::new(x, y, z + 1),
+        ]
+    }
+}
+
+impl FromStr for Voxel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let vs: Vec<i64> = s
+            .split(',')
+            .map(|v| {
+                v.trim()
+                    .parse()
+                    .map_err(|_| format!("Bad coordinate {}", v))
+            })
+            .collect::<Result<_, _>>()?;
+        match vs[..] {
+            [x, y, z] => Ok(Voxel::new(x, y, z)),
+            _ => Err(format!("Voxel {} doesn't have three coordinates", s)),
+        }
+    }
+}
+
+/// The smallest box holding some voxels, inclusive at both ends.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Bounds {
+    pub min: Voxel,
+    pub max: Voxel,
+}
+
+impl Bounds {
+    pub fn contains(&self, v: &Voxel) -> bool {
+        (self.min.x..=self.max.x).contains(&v.x)
+            && (self.min.y..=self.max.y).contains(&v.y)
+            && (self.min.z..=self.max.z).contains(&v.z)
+    }
+
+    /// The box with `by` more voxels on every side.
+    pub fn grow(&self, by: i64) -> Bounds {
+        let Bounds { min, max } = *self;
+        Bounds {
+            min: Voxel::new(min.x - by, min.y - by, min.z - by),
+            max: Voxel::new(max.x + by, max.y + by, max.z + by),
+        }
+    }
+
+    pub fn voxels(&self) -> impl Iterator<Item = Voxel> + '_ {
+        (self.min.x..=self.max.x).flat_map(move |x| {
+            (self.min.y..=self.max.y)
+                .flat_map(move |y| (self.min.z..=self.max.z).map(move |z| Voxel::new(x, y, z)))
+        })
+    }
+}
+
+/// A set of unit cubes, such as a lava droplet.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct VoxelSet {
+    voxels: HashSet<Voxel>,
+}
+
+impl FromIterator<Voxel> for VoxelSet {
+    fn from_iter<I: IntoIterator<Item = Voxel>>(iter: I) -> Self {
+        Self {
+            voxels: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl FromStr for VoxelSet {
+    type Err = String;
+
+    /// One voxel per line, like `2,2,-2`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(str::parse)
+            .collect()
+    }
+}
+
+impl VoxelSet {
+    pub fn len(&self) -> usize {
+        self.voxels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.voxels.is_empty()
+    }
+
+    pub fn contains(&self, v: &Voxel) -> bool {
+        self.voxels.contains(v)
+    }
+
+    pub fn insert(&mut self, v: Voxel) -> bool {
+        self.voxels.insert(v)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Voxel> {
+        self.voxels.iter()
+    }
+
+    pub fn bounds(&self) -> Option<Bounds> {
+        let first = *self.voxels.iter().next()?;
+        Some(self.voxels.iter().fold(
+            Bounds {
+                min: first,
+                max: first,
+            },
+            |Bounds { min, max }, v| Bounds {
+                min: Voxel::new(min.x.min(v.x), min.y.min(v.y), min.z.min(v.z)),
+                max: Voxel::new(max.x.max(v.x), max.y.max(v.y), max.z.max(v.z)),
+            },
+        ))
+    }
+
+    /// How many faces aren't shared with another voxel in the set.
+    pub fn surface_area(&self) -> usize {
+        self.voxels
+            .iter()
+            .flat_map(Voxel::neighbours)
+            .filter(|n| !self.contains(n))
+            .count()
+    }
+
+    /// Every voxel in `within` reachable from `start` through face-sharing
+    /// steps that satisfy `open`.
+    fn flood(start: Voxel, within: &Bounds, open: impl Fn(&Voxel) -> bool) -> VoxelSet {
+        let mut seen = VoxelSet::default();
+        seen.insert(start);
+        let mut stack = vec![start];
+        while let Some(v) = stack.pop() {
+            for n in v.neighbours() {
+                if within.contains(&n) && open(&n) && seen.insert(n) {
+                    stack.push(n);
+                }
+            }
+        }
+        seen
+    }
+
+    /// The air outside the set, within a box one bigger than its bounds all
+    /// round, so the air can get all the way round it.
+    pub fn exterior(&self) -> VoxelSet {
+        let Some(bounds) = self.bounds() else {
+            return VoxelSet::default();
+        };
+        let outside = bounds.grow(1);
+        Self::flood(outside.min, &outside, |v| !self.contains(v))
+    }
+
+    /// How many faces touch the air outside, leaving out the faces of
+    /// pockets sealed inside.
+    pub fn exterior_surface_area(&self) -> usize {
+        let exterior = self.exterior();
+        self.voxels
+            .iter()
+            .flat_map(Voxel::neighbours)
+            .filter(|n| exterior.contains(n))
+            .count()
+    }
+
+    /// The pockets of air sealed inside the set, each as its own set.
+    pub fn pockets(&self) -> Vec<VoxelSet> {
+        let Some(bounds) = self.bounds() else {
+            return vec![];
+        };
+        let exterior = self.exterior();
+        bounds
+            .voxels()
+            .filter(|v| !self.contains(v) && !exterior.contains(v))
+            .collect::<VoxelSet>()
+            .components()
+    }
+
+    /// The set split into pieces, where voxels sharing a face are in the
+    /// same piece.
+    pub fn components(&self) -> Vec<VoxelSet> {
+        let Some(bounds) = self.bounds() else {
+            return vec![];
+        };
+        let mut seen: HashSet<Voxel> = HashSet::new();
+        let mut components = vec![];
+        for &v in &self.voxels {
+            if seen.contains(&v) {
+                continue;
+            }
+            let component = Self::flood(v, &bounds, |n| self.contains(n));
+            seen.extend(component.iter());
+            components.push(component);
+        }
+        components
+    }
+}
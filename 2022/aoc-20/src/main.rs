@@ -1,52 +1,21 @@
+use aoc_19::mixer::grove_coordinates;
 use aoc_19::read_stdin;
 use std::time::Instant;
 
-type N = (usize, isize);
-type Input = Vec<N>;
+const DECRYPTION_KEY: isize = 811589153;
 
-fn parse(input: String) -> Input {
-    input
-        .lines()
-        .map(|l| l.parse().unwrap())
-        .enumerate()
-        .collect()
-}
-
-fn shuffle(mut input: Input) -> Input {
-    let mut j = 0;
-    for i in 0..input.len() {
-        let mut v = input[j];
-        while v.0 != i {
-            j = (j + 1) % input.len();
-            v = input[j]
-        }
+type Input = Vec<isize>;
 
-        input.remove(j);
-        let new_pos = ((j as isize) + v.1 - 1).rem_euclid(input.len() as isize) + 1;
-        input.insert(new_pos as usize, v);
-    }
-    input
+fn parse(input: String) -> Input {
+    input.lines().map(|l| l.trim().parse().unwrap()).collect()
 }
 
-fn part_1(mut input: Input) -> isize {
-    input = shuffle(input);
-    let l = input.iter().position(|v| v.1 == 0).unwrap();
-    input[(l + 1000) % input.len()].1
-        + input[(l + 2000) % input.len()].1
-        + input[(l + 3000) % input.len()].1
+fn part_1(input: &Input) -> isize {
+    grove_coordinates(input, 1, 1).unwrap()
 }
 
-fn part_2(mut input: Input) -> isize {
-    input = input.into_iter().map(|v| (v.0, v.1 * 811589153)).collect();
-
-    for _ in 0..10 {
-        input = shuffle(input);
-    }
-
-    let l = input.iter().position(|v| v.1 == 0).unwrap();
-    input[(l + 1000) % input.len()].1
-        + input[(l + 2000) % input.len()].1
-        + input[(l + 3000) % input.len()].1
+fn part_2(input: &Input) -> isize {
+    grove_coordinates(input, DECRYPTION_KEY, 10).unwrap()
 }
 
 fn main() {
@@ -57,11 +26,11 @@ fn main() {
     println!("Running parsing took {}ms.", elapsed_time.as_millis());
 
     let now = Instant::now();
-    println!("Answer 1: {}", part_1(lines.clone()));
+    println!("Answer 1: {}", part_1(&lines));
     let elapsed_time = now.elapsed();
     println!("Running part_1 took {}ms.", elapsed_time.as_millis());
 
     let now = Instant::now();
-    println!("Answer 2: {}", part_2(lines));
+    println!("Answer 2: {}", part_2(&lines));
     println!("Running part_2 took {}ms.", now.elapsed().as_millis());
 }
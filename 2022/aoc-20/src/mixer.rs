@@ -0,0 +1,212 @@
+/// No node: an empty subtree, or the parent of the root.
+const NIL: usize = usize::MAX;
+
+#[derive(Debug, Clone)]
+struct Node {
+    value: isize,
+    priority: u64,
+    left: usize,
+    right: usize,
+    parent: usize,
+    size: usize,
+}
+
+/// The circular list of numbers being mixed, as an implicit treap: a tree
+/// ordered by position in the list rather than by value, kept balanced by
+/// random heap priorities. Node `i` is always the number that started at
+/// index `i`, and nodes know their parents, so finding where a number has
+/// moved to, moving it and looking up a position all take O(log n).
+#[derive(Debug, Clone)]
+pub struct Mixer {
+    nodes: Vec<Node>,
+    root: usize,
+}
+
+impl Mixer {
+    pub fn new(values: &[isize]) -> Self {
+        // Xorshift, so the tree has the same shape every run.
+        let mut seed: u64 = 0x9e37_79b9_7f4a_7c15;
+        let nodes = values
+            .iter()
+            .map(|&value| {
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                Node {
+                    value,
+                    priority: seed,
+                    left: NIL,
+                    right: NIL,
+                    parent: NIL,
+                    size: 1,
+                }
+            })
+            .collect();
+
+        let mut mixer = Self { nodes, root: NIL };
+        for i in 0..values.len() {
+            mixer.root = mixer.merge(mixer.root, i);
+        }
+        mixer
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    fn size(&self, t: usize) -> usize {
+        if t == NIL {
+            0
+        } else {
+            self.nodes[t].size
+        }
+    }
+
+    /// Fixes up the size of `t` and the parents of its children after they
+    /// change.
+    fn update(&mut self, t: usize) {
+        let Node { left, right, .. } = self.nodes[t];
+        self.nodes[t].size = 1 + self.size(left) + self.size(right);
+        for child in [left, right] {
+            if child != NIL {
+                self.nodes[child].parent = t;
+            }
+        }
+    }
+
+    /// Joins two trees, everything in `a` coming before everything in `b`.
+    fn merge(&mut self, a: usize, b: usize) -> usize {
+        let root = if a == NIL || b == NIL {
+            if a == NIL {
+                b
+            } else {
+                a
+            }
+        } else if self.nodes[a].priority > self.nodes[b].priority {
+            let right = self.nodes[a].right;
+            self.nodes[a].right = self.merge(right, b);
+            self.update(a);
+            a
+        } else {
+            let left = self.nodes[b].left;
+            self.nodes[b].left = self.merge(a, left);
+            self.update(b);
+            b
+        };
+        if root != NIL {
+            self.nodes[root].parent = NIL;
+        }
+        root
+    }
+
+    /// Splits a tree into its first `k` numbers and the rest.
+    fn split(&mut self, t: usize, k: usize) -> (usize, usize) {
+        if t == NIL {
+            return (NIL, NIL);
+        }
+        let left = self.nodes[t].left;
+        let (a, b) = if self.size(left) < k {
+            let right = self.nodes[t].right;
+            let (a, b) = self.split(right, k - self.size(left) - 1);
+            self.nodes[t].right = a;
+            self.update(t);
+            (t, b)
+        } else {
+            let (a, b) = self.split(left, k);
+            self.nodes[t].left = b;
+            self.update(t);
+            (a, t)
+        };
+        for root in [a, b] {
+            if root != NIL {
+                self.nodes[root].parent = NIL;
+            }
+        }
+        (a, b)
+    }
+
+    /// Where the number that started at index `i` is now.
+    pub fn position(&self, i: usize) -> usize {
+        let mut t = i;
+        let mut position = self.size(self.nodes[t].left);
+        while self.nodes[t].parent != NIL {
+            let parent = self.nodes[t].parent;
+            if self.nodes[parent].right == t {
+                position += self.size(self.nodes[parent].left) + 1;
+            }
+            t = parent;
+        }
+        position
+    }
+
+    /// The number now at `position`, counting round the circle.
+    pub fn get(&self, position: usize) -> isize {
+        let mut k = position % self.len();
+        let mut t = self.root;
+        loop {
+            let left = self.nodes[t].left;
+            match k.cmp(&self.size(left)) {
+                std::cmp::Ordering::Less => t = left,
+                std::cmp::Ordering::Equal => return self.nodes[t].value,
+                std::cmp::Ordering::Greater => {
+                    k -= self.size(left) + 1;
+                    t = self.nodes[t].right;
+                }
+            }
+        }
+    }
+
+    /// Moves the number that started at index `i` forward `steps` places
+    /// round the circle. With the number itself lifted out there are only
+    /// `n - 1` places it can go, so that's the period.
+    pub fn move_by(&mut self, i: usize, steps: isize) {
+        if self.len() < 2 {
+            return;
+        }
+        let from = self.position(i);
+        let to = (from as isize + steps).rem_euclid(self.len() as isize - 1) as usize;
+
+        let (before, rest) = self.split(self.root, from);
+        let (node, after) = self.split(rest, 1);
+        let others = self.merge(before, after);
+        let (before, after) = self.split(others, to);
+        let before = self.merge(before, node);
+        self.root = self.merge(before, after);
+    }
+
+    /// Moves every number by its own value, in the order they started in.
+    pub fn mix(&mut self) {
+        for i in 0..self.len() {
+            self.move_by(i, self.nodes[i].value);
+        }
+    }
+
+    /// The numbers in their current order.
+    pub fn order(&self) -> Vec<isize> {
+        (0..self.len()).map(|p| self.get(p)).collect()
+    }
+
+    /// The number `offset` places after the `0`, if there is one.
+    pub fn after_zero(&self, offset: usize) -> Option<isize> {
+        let zero = self.nodes.iter().position(|n| n.value == 0)?;
+        Some(self.get(self.position(zero) + offset))
+    }
+}
+
+/// Multiplies every number by `key`, mixes `rounds` times and sums the
+/// numbers 1000, 2000 and 3000 places after the `0`.
+pub fn grove_coordinates(values: &[isize], key: isize, rounds: usize) -> Option<isize> {
+    let values: Vec<isize> = values.iter().map(|v| v * key).collect();
+    let mut mixer = Mixer::new(&values);
+    for _ in 0..rounds {
+        mixer.mix();
+    }
+    [1000, 2000, 3000]
+        .iter()
+        .map(|&offset| mixer.after_zero(offset))
+        .sum()
+}
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+type Vec3 = [i8; 3];
+
+fn neg(v: Vec3) -> Vec3 {
+    [-v[0], -v[1], -v[2]]
+}
+
+/// Edge directions use the puzzle's facing values: 0 = right, 1 = down,
+/// 2 = left, 3 = up.
+fn opposite(edge: usize) -> usize {
+    (edge + 2) % 4
+}
+
+/// Where a face of the net ended up once folded: its outward normal, and
+/// which way its local x and y axes point in 3D.
+#[derive(Clone, Copy, Debug)]
+struct Face {
+    block: (isize, isize),
+    normal: Vec3,
+    right: Vec3,
+    down: Vec3,
+}
+
+impl Face {
+    /// The 3D direction pointing out of this face across the given edge.
+    fn edge_dir(&self, edge: usize) -> Vec3 {
+        match edge {
+            0 => self.right,
+            1 => self.down,
+            2 => neg(self.right),
+            _ => neg(self.down),
+        }
+    }
+
+    /// The 3D direction positions along the given edge increase in.
+    fn along(&self, edge: usize) -> Vec3 {
+        if edge.is_multiple_of(2) {
+            self.down
+        } else {
+            self.right
+        }
+    }
+
+    /// The face the net shows next to this one in the given direction, folded
+    /// over that edge.
+    fn neighbour(&self, edge: usize, block: (isize, isize)) -> Self {
+        let (normal, right, down) = match edge {
+            0 => (self.right, neg(self.normal), self.down),
+            1 => (self.down, self.right, neg(self.normal)),
+            2 => (neg(self.right), self.normal, self.down),
+            _ => (neg(self.down), self.right, self.normal),
+        };
+        Self {
+            block,
+            normal,
+            right,
+            down,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Transition {
+    face: usize,
+    edge: usize,
+    flipped: bool,
+}
+
+/// A cube folded from a flat net, used to wrap positions that walk off the
+/// edge of one face onto the adjacent face.
+#[derive(Clone, Debug)]
+pub struct Cube {
+    pub size: isize,
+    faces: Vec<Face>,
+    blocks: HashMap<(isize, isize), usize>,
+    transitions: Vec<[Transition; 4]>,
+}
+
+impl Cube {
+    /// Folds the net made up of the given tiles. Returns `None` if the tiles
+    /// don't form one of the cube nets.
+    pub fn fold(tiles: impl IntoIterator<Item = (isize, isize)>) -> Option<Self> {
+        let tiles: Vec<(isize, isize)> = tiles.into_iter().collect();
+        let size = (1..)
+            .take_while(|s| 6 * s * s <= tiles.len() as isize)
+            .last()?;
+        if 6 * size * size != tiles.len() as isize {
+            return None;
+        }
+
+        let mut counts: HashMap<(isize, isize), isize> = HashMap::new();
+        for &(x, y) in &tiles {
+            *counts
+                .entry((x.div_euclid(size), y.div_euclid(size)))
+                .or_default() += 1;
+        }
+        if counts.len() != 6 || counts.values().any(|&c| c != size * size) {
+            return None;
+        }
+
+        let start = *counts.keys().min_by_key(|(x, y)| (*y, *x))?;
+        let mut faces = vec![Face {
+            block: start,
+            normal: [0, 0, -1],
+            right: [1, 0, 0],
+            down: [0, 1, 0],
+        }];
+        let mut blocks = HashMap::from([(start, 0)]);
+        let mut i = 0;
+        while i < faces.len() {
+            let face = faces[i];
+            let (bx, by) = face.block;
+            for (edge, block) in [(bx + 1, by), (bx, by + 1), (bx - 1, by), (bx, by - 1)]
+                .into_iter()
+                .enumerate()
+            {
+                if counts.contains_key(&block) && !blocks.contains_key(&block) {
+                    blocks.insert(block, faces.len());
+                    faces.push(face.neighbour(edge, block));
+                }
+            }
+            i += 1;
+        }
+        if faces.len() != 6 {
+            return None;
+        }
+
+        let transitions = faces
+            .iter()
+            .map(|face| {
+                let mut ts = [Transition {
+                    face: 0,
+                    edge: 0,
+                    flipped: false,
+                }; 4];
+                for (edge, t) in ts.iter_mut().enumerate() {
+                    let dir = face.edge_dir(edge);
+                    let target = faces.iter().position(|f| f.normal == dir)?;
+                    let target_edge = (0..4).find(|&e| faces[target].edge_dir(e) == face.normal)?;
+                    *t = Transition {
+                        face: target,
+                        edge: target_edge,
+                        flipped: faces[target].along(target_edge) != face.along(edge),
+                    };
+                }
+                Some(ts)
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Self {
+            size,
+            faces,
+            blocks,
+            transitions,
+        })
+    }
+
+    /// Given a position on the net and the facing it is about to leave its
+    /// face with, returns the position and facing after crossing the edge.
+    pub fn wrap(&self, (x, y): (isize, isize), facing: usize) -> ((isize, isize), usize) {
+        let n = self.size;
+        let face = self.blocks[&(x.div_euclid(n), y.div_euclid(n))];
+        let (lx, ly) = (x.rem_euclid(n), y.rem_euclid(n));
+
+        let Transition {
+            face: target,
+            edge,
+            flipped,
+        } = self.transitions[face][facing];
+
+        let t = if facing.is_multiple_of(2) { ly } else { lx };
+        let t = if flipped { n - 1 - t } else { t };
+        let (lx, ly) = match edge {
+            0 => (n - 1, t),
+            1 => (t, n - 1),
+            2 => (0, t),
+            _ => (t, 0),
+        };
+
+        let (bx, by) = self.faces[target].block;
+        ((bx * n + lx, by * n + ly), opposite(edge))
+    }
+}
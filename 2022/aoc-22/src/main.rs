@@ -1,10 +1,8 @@
-use aoc_19::read_stdin;
+use aoc_19::{cube::Cube, read_stdin};
 
 use std::collections::HashMap;
 use std::time::Instant;
 
-const FACE_SIZE: isize = 50;
-
 #[derive(Clone, Debug, Copy, Eq, PartialEq, Hash)]
 struct Coord {
     x: isize,
@@ -15,36 +13,6 @@ impl Coord {
     fn new(x: isize, y: isize) -> Self {
         Self { x, y }
     }
-
-    fn rotate_r(self) -> Self {
-        Self::new(FACE_SIZE - self.y - 1, self.x)
-    }
-
-    fn rotate_l(self) -> Self {
-        Self::new(self.y, FACE_SIZE - self.x - 1)
-    }
-
-    fn flip_y(self) -> Self {
-        Self::new(FACE_SIZE - self.x - 1, self.y)
-    }
-
-    fn flip_x(self) -> Self {
-        Self::new(self.x, FACE_SIZE - self.y - 1)
-    }
-
-    fn get_face(&self) -> Face {
-        let x = self.x / FACE_SIZE;
-        let y = self.y / FACE_SIZE;
-        match (x, y) {
-            (1, 0) => Face::One,
-            (2, 0) => Face::Two,
-            (0, 2) => Face::Three,
-            (1, 1) => Face::Four,
-            (1, 2) => Face::Five,
-            (0, 3) => Face::Six,
-            _ => panic!("Can't find facing for point {:?}", self),
-        }
-    }
 }
 
 impl std::ops::Add for Coord {
@@ -111,6 +79,15 @@ impl Facing {
             Facing::U => 3,
         }
     }
+
+    fn from_val(val: usize) -> Self {
+        match val % 4 {
+            0 => Facing::R,
+            1 => Facing::D,
+            2 => Facing::L,
+            _ => Facing::U,
+        }
+    }
 }
 
 type Map = HashMap<Coord, char>;
@@ -227,116 +204,13 @@ fn part_1(Input { map, directions }: Input) -> isize {
     1000 * (pos.y + 1) + 4 * (pos.x + 1) + facing.val()
 }
 
-#[derive(Clone, Debug, Copy)]
-enum Face {
-    One,
-    Two,
-    Three,
-    Four,
-    Five,
-    Six,
-}
-
-impl Face {
-    fn relative(&self) -> Coord {
-        match self {
-            Face::One => Coord::new(FACE_SIZE, 0),
-            Face::Two => Coord::new(2 * FACE_SIZE, 0),
-            Face::Three => Coord::new(0, 2 * FACE_SIZE),
-            Face::Four => Coord::new(FACE_SIZE, FACE_SIZE),
-            Face::Five => Coord::new(FACE_SIZE, 2 * FACE_SIZE),
-            Face::Six => Coord::new(0, 3 * FACE_SIZE),
-        }
-    }
-
-    fn wrap_up(&self, pos: Coord) -> (Coord, Facing) {
-        match self {
-            Face::One => (
-                (pos - self.relative()).flip_y().rotate_l() + Face::Six.relative(),
-                Facing::R,
-            ),
-            Face::Two => (
-                (pos - self.relative()).flip_x() + Face::Six.relative(),
-                Facing::U,
-            ),
-            Face::Three => (
-                (pos - self.relative()).flip_y().rotate_l() + Face::Four.relative(),
-                Facing::R,
-            ),
-            Face::Four => panic!("Can't exit top of {:?}", self),
-            Face::Five => panic!("Can't exit top of {:?}", self),
-            Face::Six => panic!("Can't exit top of {:?}", self),
-        }
-    }
-
-    fn wrap_right(&self, pos: Coord) -> (Coord, Facing) {
-        match self {
-            Face::One => panic!("Can't exit right of {:?}", self),
-            Face::Two => (
-                (pos - self.relative()).flip_x() + Face::Five.relative(),
-                Facing::L,
-            ),
-            Face::Three => panic!("Can't exit right of {:?}", self),
-            Face::Four => (
-                (pos - self.relative()).flip_x().rotate_r() + Face::Two.relative(),
-                Facing::U,
-            ),
-            Face::Five => (
-                (pos - self.relative()).flip_x() + Face::Two.relative(),
-                Facing::L,
-            ),
-            Face::Six => (
-                (pos - self.relative()).flip_x().rotate_r() + Face::Five.relative(),
-                Facing::U,
-            ),
-        }
-    }
-
-    fn wrap_down(&self, pos: Coord) -> (Coord, Facing) {
-        match self {
-            Face::One => panic!("Can't exit bottom of {:?}", self),
-            Face::Two => (
-                (pos - self.relative()).flip_y().rotate_l() + Face::Four.relative(),
-                Facing::L,
-            ),
-            Face::Three => panic!("Can't exit bottom of {:?}", self),
-            Face::Four => panic!("Can't exit bottom of {:?}", self),
-            Face::Five => (
-                (pos - self.relative()).flip_y().rotate_l() + Face::Six.relative(),
-                Facing::L,
-            ),
-            Face::Six => (
-                (pos - self.relative()).flip_x() + Face::Two.relative(),
-                Facing::D,
-            ),
-        }
-    }
-
-    fn wrap_left(&self, pos: Coord) -> (Coord, Facing) {
-        match self {
-            Face::One => (
-                (pos - self.relative()).flip_x() + Face::Three.relative(),
-                Facing::R,
-            ),
-            Face::Two => panic!("Can't exit left of {:?}", self),
-            Face::Three => (
-                (pos - self.relative()).flip_x() + Face::One.relative(),
-                Facing::R,
-            ),
-            Face::Four => (
-                (pos - self.relative()).flip_y().rotate_l() + Face::Three.relative(),
-                Facing::D,
-            ),
-            Face::Five => panic!("Can't exit left of {:?}", self),
-            Face::Six => (
-                (pos - self.relative()).flip_y().rotate_l() + Face::One.relative(),
-                Facing::D,
-            ),
-        }
-    }
-}
-
-fn new_pos_2(mut pos: Coord, dist: usize, mut facing: Facing, map: &Map) -> (Coord, Facing) {
+fn new_pos_2(
+    mut pos: Coord,
+    dist: usize,
+    mut facing: Facing,
+    map: &Map,
+    cube: &Cube,
+) -> (Coord, Facing) {
     for _ in 0..dist {
         let (mut new_pos, mut new_facing) = match facing {
             Facing::U => (Coord::new(pos.x, pos.y - 1), facing),
@@ -346,12 +220,9 @@ fn new_pos_2(mut pos: Coord, dist: usize, mut facing: Facing, map: &Map) -> (Coo
         };
 
         if !map.contains_key(&new_pos) {
-            (new_pos, new_facing) = match facing {
-                Facing::U => pos.get_face().wrap_up(pos),
-                Facing::R => pos.get_face().wrap_right(pos),
-                Facing::D => pos.get_face().wrap_down(pos),
-                Facing::L => pos.get_face().wrap_left(pos),
-            };
+            let ((x, y), f) = cube.wrap((pos.x, pos.y), facing.val() as usize);
+            new_pos = Coord::new(x, y);
+            new_facing = Facing::from_val(f);
         }
 
         if map[&new_pos] == '.' {
@@ -366,6 +237,8 @@ fn new_pos_2(mut pos: Coord, dist: usize, mut facing: Facing, map: &Map) -> (Coo
 }
 
 fn part_2(Input { map, directions }: Input) -> isize {
+    let cube = Cube::fold(map.keys().map(|p| (p.x, p.y))).expect("Map isn't a cube net");
+
     let mut pos = Coord {
         x: map
             .iter()
@@ -380,7 +253,7 @@ fn part_2(Input { map, directions }: Input) -> isize {
 
     for direction in directions {
         match direction {
-            Direction::Move(dist) => (pos, facing) = new_pos_2(pos, dist, facing, &map),
+            Direction::Move(dist) => (pos, facing) = new_pos_2(pos, dist, facing, &map, &cube),
             Direction::L => facing = facing.turn_left(),
             Direction::R => facing = facing.turn_right(),
         }
@@ -0,0 +1,284 @@
+use std::fmt::Write;
+
+pub type Id = usize;
+
+pub const ROOT: Id = 0;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FsError {
+    CdAboveRoot,
+    NoSuchDir(String),
+    UnknownCommand(String),
+    BadListing(String),
+    OutputWithoutLs(String),
+}
+
+#[derive(Clone, Debug)]
+enum Kind {
+    Dir(Vec<Id>),
+    File,
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    name: String,
+    parent: Option<Id>,
+    kind: Kind,
+    /// For files their own size, for directories the total of everything
+    /// under them.
+    size: usize,
+}
+
+/// A directory tree rebuilt from a `cd`/`ls` terminal transcript, with
+/// directory sizes totalled once up front.
+#[derive(Clone, Debug)]
+pub struct Tree {
+    nodes: Vec<Node>,
+}
+
+impl Tree {
+    pub fn from_transcript(input: &str) -> Result<Self, FsError> {
+        let mut tree = Self {
+            nodes: vec![Node {
+                name: "/".to_string(),
+                parent: None,
+                kind: Kind::Dir(vec![]),
+                size: 0,
+            }],
+        };
+
+        let mut curr = ROOT;
+        let mut listing = false;
+        for line in input.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            if let Some(cmd) = line.strip_prefix("$ ") {
+                listing = false;
+                match cmd.split_once(' ') {
+                    Some(("cd", "/")) => curr = ROOT,
+                    Some(("cd", "..")) => {
+                        curr = tree.nodes[curr].parent.ok_or(FsError::CdAboveRoot)?
+                    }
+                    Some(("cd", name)) => {
+                        curr = tree
+                            .child(curr, name)
+                            .filter(|&c| tree.is_dir(c))
+                            .ok_or_else(|| FsError::NoSuchDir(tree.path(curr) + name))?
+                    }
+                    None if cmd == "ls" => listing = true,
+                    _ => return Err(FsError::UnknownCommand(cmd.to_string())),
+                }
+                continue;
+            }
+
+            if !listing {
+                return Err(FsError::OutputWithoutLs(line.to_string()));
+            }
+            let (info, name) = line
+                .split_once(' ')
+                .ok_or_else(|| FsError::BadListing(line.to_string()))?;
+            if tree.child(curr, name).is_some() {
+                continue;
+            }
+            let (kind, size) = match info {
+                "dir" => (Kind::Dir(vec![]), 0),
+                size => (
+                    Kind::File,
+                    size.parse()
+                        .map_err(|_| FsError::BadListing(line.to_string()))?,
+                ),
+            };
+            tree.add(curr, name, kind, size);
+        }
+
+        // Children always come after their parent, so a single backwards pass
+        // totals every directory.
+        for id in (1..tree.nodes.len()).rev() {
+            let size = tree.nodes[id].size;
+            if let Some(parent) = tree.nodes[id].parent {
+                tree.nodes[parent].size += size;
+            }
+        }
+
+        Ok(tree)
+    }
+
+    fn add(&mut self, parent: Id, name: &str, kind: Kind, size: usize) {
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            name: name.to_string(),
+            parent: Some(parent),
+            kind,
+            size,
+        });
+        if let Kind::Dir(children) = &mut self.nodes[parent].kind {
+            children.push(id);
+        }
+    }
+
+    fn children(&self, id: Id) -> &[Id] {
+        match &self.nodes[id].kind {
+            Kind::Dir(children) => children,
+            Kind::File => &[],
+        }
+    }
+
+    fn child(&self, id: Id, name: &str) -> Option<Id> {
+        self.children(id)
+            .iter()
+            .copied()
+            .find(|&c| self.nodes[c].name == name)
+    }
+
+    pub fn is_dir(&self, id: Id) -> bool {
+        matches!(self.nodes[id].kind, Kind::Dir(_))
+    }
+
+    pub fn size(&self, id: Id) -> usize {
+        self.nodes[id].size
+    }
+
+    /// The absolute path of a node, with a trailing `/` for directories.
+    pub fn path(&self, id: Id) -> String {
+        match self.nodes[id].parent {
+            None => "/".to_string(),
+            Some(parent) => {
+                let mut path = self.path(parent) + &self.nodes[id].name;
+                if self.is_dir(id) {
+                    path.push('/');
+                }
+                path
+            }
+        }
+    }
+
+    pub fn dirs(&self) -> impl Iterator<Item = Id> + '_ {
+        (0..self.nodes.len()).filter(|&id| self.is_dir(id))
+    }
+
+    /// Every directory whose total size is at most `limit`.
+    pub fn dirs_at_most(&self, limit: usize) -> impl Iterator<Item = Id> + '_ {
+        self.dirs().filter(move |&id| self.size(id) <= limit)
+    }
+
+    /// The smallest directory that, once deleted, leaves at least `needed`
+    /// free on a disk of `capacity`.
+    pub fn smallest_dir_freeing(&self, capacity: usize, needed: usize) -> Option<Id> {
+        let free = capacity.saturating_sub(self.size(ROOT));
+        let to_free = needed.saturating_sub(free);
+        self.dirs()
+            .filter(|&id| self.size(id) >= to_free)
+            .min_by_key(|&id| self.size(id))
+    }
+
+    /// Renders the tree like the puzzle's own listing, one node per line.
+    pub fn render_tree(&self) -> String {
+        let mut out = String::new();
+        self.render_node(ROOT, 0, &mut out);
+        out
+    }
+
+    fn render_node(&self, id: Id, depth: usize, out: &mut String) {
+        let kind = if self.is_dir(id) { "dir" } else { "file" };
+        let _ = writeln!(
+            out,
+            "{}- {} ({}, size={})",
+            "  ".repeat(depth),
+            self.nodes[id].name,
+            kind,
+            self.size(id)
+        );
+        for &c in self.children(id) {
+            self.render_node(c, depth + 1, out);
+        }
+    }
+
+    /// Renders a `du -h` style report: every directory with its
+    /// human-readable total, children before their parents.
+    pub fn render_du(&self) -> String {
+        let mut out = String::new();
+        self.du_node(ROOT, &mut out);
+        out
+    }
+
+    fn du_node(&self, id: Id, out: &mut String) {
+        for &c in self.children(id) {
+            if self.is_dir(c) {
+                self.du_node(c, out);
+            }
+        }
+        let _ = writeln!(out, "{}\t{}", human(self.size(id)), self.path(id));
+    }
+}
+
+/// Sizes the way `du -h` shows them: rounded up, to a tenth below 10.
+fn human(size: usize) -> String {
+    if size < 1024 {
+        return size.to_string();
+    }
+    let mut size = size as f64 / 1024.0;
+    for unit in ["K", "M", "G"] {
+        let tenths = (size * 10.0).ceil();
+        if tenths < 100.0 {
+            return format!("{:.1}{}", tenths / 10.0, unit);
+        }
+        if size.ceil() < 1024.0 {
+            return format!("{}{}", size.ceil(), unit);
+        }
+        size /= 1024.0;
+    }
+    format!("{}T", size.ceil())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k";
+
+    #[test]
+    fn human_rounds_up() {
+        let cases = [
+            (0, "0"),
+            (584, "584"),
+            (1023, "1023"),
+            (1024, "1.0K"),
+            (1025, "1.1K"),
+            (94853, "93K"),
+            (1048575, "1.0M"),
+            (8504156, "8.2M"),
+            (14848514, "15M"),
+            (48381165, "47M"),
+        ];
+        for (size, expected) in cases {
+            assert_eq!(human(size), expected, "{}", size);
+        }
+    }
+
+    #[test]
+    fn example_du() {
+        let tree = Tree::from_transcript(EXAMPLE).unwrap();
+        assert_eq!(tree.render_du(), "584\t/a/e/\n93K\t/a/\n24M\t/d/\n47M\t/\n");
+    }
+}
@@ -0,0 +1,92 @@
+/// Which cards beat which, and which of them are wild.
+#[derive(Debug, Clone)]
+pub struct Rules {
+    /// Card labels from weakest to strongest.
+    order: Vec<char>,
+    /// Labels that stand in for whichever card makes the hand strongest.
+    /// They still break ties by their place in `order`.
+    wild: Vec<char>,
+}
+
+/// A hand, ordered first by its shape and then card by card.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Hand {
+    /// The sizes of the groups of matching cards, largest first, with the
+    /// wildcards already added to the largest group. `[3, 2]` is a full
+    /// house, `[1, 1, 1, 1, 1]` a high card.
+    pub shape: Vec<usize>,
+    /// Each card's rank, in the order they were dealt.
+    pub ranks: Vec<usize>,
+}
+
+impl Rules {
+    pub fn new(order: &str, wild: &str) -> Self {
+        Self {
+            order: order.chars().collect(),
+            wild: wild.chars().collect(),
+        }
+    }
+
+    /// Camel cards without jokers.
+    pub fn standard() -> Self {
+        Self::new("23456789TJQKA", "")
+    }
+
+    /// Camel cards where `J` is a joker, and the weakest card on its own.
+    pub fn jokers() -> Self {
+        Self::new("J23456789TQKA", "J")
+    }
+
+    fn rank(&self, card: char) -> Option<usize> {
+        self.order.iter().position(|&c| c == card)
+    }
+
+    /// Classifies a hand of any size.
+    pub fn hand(&self, cards: &str) -> Option<Hand> {
+        let ranks = cards
+            .chars()
+            .map(|c| self.rank(c))
+            .collect::<Option<Vec<_>>>()?;
+
+        let mut counts = vec![0; self.order.len()];
+        let mut wild = 0;
+        for c in cards.chars() {
+            if self.wild.contains(&c) {
+                wild += 1;
+            } else {
+                counts[self.rank(c)?] += 1;
+            }
+        }
+
+        let mut shape: Vec<usize> = counts.into_iter().filter(|&n| n > 0).collect();
+        shape.sort_unstable_by(|a, b| b.cmp(a));
+        match shape.first_mut() {
+            Some(largest) => *largest += wild,
+            None if wild > 0 => shape.push(wild),
+            None => (),
+        }
+
+        Some(Hand { shape, ranks })
+    }
+
+    /// Reads one hand and bid per line.
+    pub fn parse_bids(&self, input: &str) -> Option<Vec<(Hand, usize)>> {
+        input
+            .lines()
+            .map(|l| {
+                let (cards, bid) = l.split_once(' ')?;
+                Some((self.hand(cards)?, bid.trim().parse().ok()?))
+            })
+            .collect()
+    }
+}
+
+/// Every bid multiplied by its hand's rank among all the hands, weakest
+/// first, added up.
+pub fn winnings(mut bids: Vec<(Hand, usize)>) -> usize {
+    bids.sort_by(|a, b| a.0.cmp(&b.0));
+    bids.iter()
+        .enumerate()
+        .map(|(i, (_, bid))| (i + 1) * bid)
+        .sum()
+}
@@ -0,0 +1,252 @@
+use rustc_hash::FxHashMap;
+use std::{collections::VecDeque, fmt::Write, str::FromStr};
+
+pub type ModuleId = usize;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Kind {
+    FlipFlop,
+    Conjunction,
+    Broadcaster,
+    /// A module that is only ever sent pulses, like `rx`.
+    Sink,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Pulse {
+    pub from: ModuleId,
+    pub to: ModuleId,
+    pub high: bool,
+}
+
+/// The wiring of a pulse circuit, with every module name interned to an id.
+#[derive(Debug, Clone)]
+pub struct Circuit {
+    names: Vec<String>,
+    ids: FxHashMap<String, ModuleId>,
+    kinds: Vec<Kind>,
+    /// Each output as the receiving module and which of its inputs it is.
+    outputs: Vec<Vec<(ModuleId, usize)>>,
+    inputs: Vec<Vec<ModuleId>>,
+    pub broadcaster: ModuleId,
+}
+
+/// The flip-flop and conjunction memory of a circuit, updated in place.
+#[derive(Debug, Clone, Eq, Hash, PartialEq)]
+pub struct State {
+    on: Vec<bool>,
+    memory: Vec<Vec<bool>>,
+    highs: Vec<usize>,
+}
+
+impl Circuit {
+    fn intern(&mut self, name: &str) -> ModuleId {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = self.names.len();
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        self.kinds.push(Kind::Sink);
+        self.outputs.push(vec![]);
+        self.inputs.push(vec![]);
+        id
+    }
+
+    pub fn id(&self, name: &str) -> Option<ModuleId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: ModuleId) -> &str {
+        &self.names[id]
+    }
+
+    pub fn kind(&self, id: ModuleId) -> Kind {
+        self.kinds[id]
+    }
+
+    pub fn inputs(&self, id: ModuleId) -> &[ModuleId] {
+        &self.inputs[id]
+    }
+
+    pub fn initial_state(&self) -> State {
+        State {
+            on: vec![false; self.names.len()],
+            memory: self.inputs.iter().map(|i| vec![false; i.len()]).collect(),
+            highs: vec![0; self.names.len()],
+        }
+    }
+
+    /// Presses the button once, calling `observe` with every pulse in the
+    /// order it is delivered. The button's own pulse comes first, shown as
+    /// the broadcaster sending to itself.
+    pub fn press(&self, state: &mut State, mut observe: impl FnMut(&Pulse)) {
+        let mut queue = VecDeque::new();
+        queue.push_back((
+            Pulse {
+                from: self.broadcaster,
+                to: self.broadcaster,
+                high: false,
+            },
+            0,
+        ));
+
+        while let Some((pulse, slot)) = queue.pop_front() {
+            observe(&pulse);
+            let Pulse { to, high, .. } = pulse;
+
+            let out = match self.kinds[to] {
+                Kind::FlipFlop if high => continue,
+                Kind::FlipFlop => {
+                    state.on[to] = !state.on[to];
+                    state.on[to]
+                }
+                Kind::Conjunction => {
+                    let remembered = &mut state.memory[to][slot];
+                    if *remembered != high {
+                        *remembered = high;
+                        if high {
+                            state.highs[to] += 1;
+                        } else {
+                            state.highs[to] -= 1;
+                        }
+                    }
+                    state.highs[to] != self.inputs[to].len()
+                }
+                Kind::Broadcaster => high,
+                Kind::Sink => continue,
+            };
+
+            for &(next, next_slot) in &self.outputs[to] {
+                queue.push_back((
+                    Pulse {
+                        from: to,
+                        to: next,
+                        high: out,
+                    },
+                    next_slot,
+                ));
+            }
+        }
+    }
+
+    /// Total low and high pulses sent over `presses` button presses.
+    pub fn count_pulses(&self, presses: usize) -> (usize, usize) {
+        let mut state = self.initial_state();
+        let mut counts = (0, 0);
+        for _ in 0..presses {
+            self.press(&mut state, |p| {
+                if p.high {
+                    counts.1 += 1
+                } else {
+                    counts.0 += 1
+                }
+            });
+        }
+        counts
+    }
+
+    /// Finds the conjunction that alone feeds `target`, and the sub-counters
+    /// feeding that conjunction.
+    pub fn feeders(&self, target: ModuleId) -> Option<(ModuleId, Vec<ModuleId>)> {
+        match self.inputs[target][..] {
+            [conj] if self.kinds[conj] == Kind::Conjunction => {
+                Some((conj, self.inputs[conj].clone()))
+            }
+            _ => None,
+        }
+    }
+
+    /// How many presses each sub-counter feeding the final conjunction takes
+    /// to send it a high pulse, checked to recur with that same period.
+    pub fn counter_periods(&self, target: ModuleId) -> Option<Vec<(ModuleId, usize)>> {
+        let (conj, counters) = self.feeders(target)?;
+        let mut seen: FxHashMap<ModuleId, Vec<usize>> = FxHashMap::default();
+        let mut state = self.initial_state();
+        let mut presses = 0;
+
+        while counters.iter().any(|c| seen.get(c).map_or(0, Vec::len) < 2) {
+            presses += 1;
+            self.press(&mut state, |p| {
+                if p.high && p.to == conj {
+                    let times = seen.entry(p.from).or_default();
+                    if times.last() != Some(&presses) {
+                        times.push(presses);
+                    }
+                }
+            });
+            // Every counter should have fired well within this many presses.
+            if presses > 1 << 20 {
+                return None;
+            }
+        }
+
+        counters
+            .iter()
+            .map(|c| match seen[c][..] {
+                [first, second, ..] if second == 2 * first => Some((*c, first)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The module graph in Graphviz DOT format.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph circuit {\n");
+        for (id, name) in self.names.iter().enumerate() {
+            let (label, shape) = match self.kinds[id] {
+                Kind::FlipFlop => (format!("%{}", name), "box"),
+                Kind::Conjunction => (format!("&{}", name), "invhouse"),
+                Kind::Broadcaster => (name.clone(), "doublecircle"),
+                Kind::Sink => (name.clone(), "circle"),
+            };
+            let _ = writeln!(out, "  {} [label=\"{}\", shape={}];", name, label, shape);
+        }
+        for (id, outputs) in self.outputs.iter().enumerate() {
+            for &(to, _) in outputs {
+                let _ = writeln!(out, "  {} -> {};", self.names[id], self.names[to]);
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+impl FromStr for Circuit {
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut circuit = Circuit {
+            names: vec![],
+            ids: FxHashMap::default(),
+            kinds: vec![],
+            outputs: vec![],
+            inputs: vec![],
+            broadcaster: 0,
+        };
+
+        for l in input.lines() {
+            let (label, outputs) = l.split_once(" -> ").ok_or(())?;
+            let (kind, name) = if let Some(name) = label.strip_prefix('%') {
+                (Kind::FlipFlop, name)
+            } else if let Some(name) = label.strip_prefix('&') {
+                (Kind::Conjunction, name)
+            } else if label == "broadcaster" {
+                (Kind::Broadcaster, label)
+            } else {
+                return Err(());
+            };
+
+            let id = circuit.intern(name);
+            circuit.kinds[id] = kind;
+            for output in outputs.split(", ") {
+                let to = circuit.intern(output);
+                circuit.outputs[id].push((to, circuit.inputs[to].len()));
+                circuit.inputs[to].push(id);
+            }
+        }
+
+        circuit.broadcaster = circuit.id("broadcaster").ok_or(())?;
+        Ok(circuit)
+    }
+}
@@ -0,0 +1,180 @@
+use rustc_hash::FxHashSet;
+use std::str::FromStr;
+
+/// How many further periods a quadratic fit has to predict correctly before
+/// it is trusted for extrapolation.
+const CONFIRMATIONS: usize = 3;
+
+/// A garden map that repeats infinitely in every direction.
+pub struct Garden {
+    plots: Vec<bool>,
+    pub width: isize,
+    pub height: isize,
+    pub start: (isize, isize),
+}
+
+impl FromStr for Garden {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<&str> = s.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+        let width = lines.first().ok_or(())?.len();
+        if lines.iter().any(|l| l.len() != width) {
+            return Err(());
+        }
+
+        let mut start = None;
+        let mut plots = Vec::with_capacity(width * lines.len());
+        for (y, l) in lines.iter().enumerate() {
+            for (x, c) in l.chars().enumerate() {
+                match c {
+                    '.' => plots.push(true),
+                    '#' => plots.push(false),
+                    'S' => {
+                        start = Some((x as isize, y as isize));
+                        plots.push(true);
+                    }
+                    _ => return Err(()),
+                }
+            }
+        }
+
+        Ok(Self {
+            plots,
+            width: width as isize,
+            height: lines.len() as isize,
+            start: start.ok_or(())?,
+        })
+    }
+}
+
+fn gcd(a: isize, b: isize) -> isize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+impl Garden {
+    pub fn is_plot(&self, (x, y): (isize, isize)) -> bool {
+        let x = x.rem_euclid(self.width);
+        let y = y.rem_euclid(self.height);
+        self.plots[(y * self.width + x) as usize]
+    }
+
+    /// Breadth-first search over the infinite tiling, yielding how many plots
+    /// are first reached after 0, 1, 2, ... steps. Stops once nothing new can
+    /// be reached.
+    pub fn layers(&self) -> impl Iterator<Item = usize> + '_ {
+        let mut seen = FxHashSet::default();
+        seen.insert(self.start);
+        let mut frontier = vec![self.start];
+
+        std::iter::from_fn(move || {
+            if frontier.is_empty() {
+                return None;
+            }
+            let size = frontier.len();
+            frontier = frontier
+                .iter()
+                .flat_map(|&(x, y)| [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)])
+                .filter(|&p| self.is_plot(p) && seen.insert(p))
+                .collect();
+            Some(size)
+        })
+    }
+
+    /// Counts the plots reachable in exactly `steps` steps by walking the
+    /// search all the way out.
+    pub fn count_brute_force(&self, steps: usize) -> usize {
+        self.layers()
+            .take(steps + 1)
+            .enumerate()
+            .filter(|(d, _)| d % 2 == steps % 2)
+            .map(|(_, n)| n)
+            .sum()
+    }
+
+    /// Counts the plots reachable in exactly `steps` steps.
+    ///
+    /// Once the search is a few tiles out, the counts sampled every period
+    /// (the lcm of the map dimensions) grow quadratically. The search runs
+    /// until that quadratic has been confirmed against brute-force counts for
+    /// a few periods, then extrapolates the rest of the way.
+    pub fn count(&self, steps: usize) -> usize {
+        let period = (self.width * self.height / gcd(self.width, self.height)) as usize;
+
+        let mut totals = [0, 0];
+        let mut samples: Vec<usize> = vec![];
+        for (d, n) in self.layers().enumerate() {
+            totals[d % 2] += n;
+            if d == steps {
+                return totals[d % 2];
+            }
+            if d % period != steps % period {
+                continue;
+            }
+
+            samples.push(totals[d % 2]);
+            let diffs: Vec<isize> = samples
+                .windows(3)
+                .map(|w| w[2] as isize - 2 * w[1] as isize + w[0] as isize)
+                .collect();
+            if diffs.len() > CONFIRMATIONS
+                && diffs[diffs.len() - CONFIRMATIONS - 1..]
+                    .windows(2)
+                    .all(|w| w[0] == w[1])
+            {
+                let k = &samples[samples.len() - 2..];
+                let (last, first_diff) = (k[1] as isize, k[1] as isize - k[0] as isize);
+                let second_diff = diffs[diffs.len() - 1];
+                let n = ((steps - d) / period) as isize;
+                return (last + n * first_diff + second_diff * n * (n + 1) / 2) as usize;
+            }
+        }
+
+        // The reachable area is finite, so every plot of the right parity is
+        // reachable eventually.
+        totals[steps % 2]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Garden;
+
+    const EXAMPLE: &str = "\
+...........
+.....###.#.
+.###.##..#.
+..#.#...#..
+....#.#....
+.##..S####.
+.##..#...#.
+.......##..
+.##.#.####.
+.##..##.##.
+...........";
+
+    #[test]
+    fn example_counts() {
+        let garden: Garden = EXAMPLE.parse().unwrap();
+        for (steps, expected) in [(6, 16), (10, 50), (50, 1594), (100, 6536), (500, 167004)] {
+            assert_eq!(garden.count(steps), expected, "{} steps", steps);
+        }
+    }
+
+    #[test]
+    fn count_matches_brute_force() {
+        let garden: Garden = EXAMPLE.parse().unwrap();
+        for steps in [6, 10, 50, 100, 133, 200, 257, 301] {
+            assert_eq!(
+                garden.count(steps),
+                garden.count_brute_force(steps),
+                "{} steps",
+                steps
+            );
+        }
+    }
+}
@@ -1,5 +1,11 @@
 use std::io::{self, Read};
 
+pub mod cards;
+pub mod circuit;
+pub mod garden;
+pub mod nonogram;
+pub mod workflow;
+
 pub fn read_stdin() -> String {
     let mut buffer = String::new();
     let stdin = io::stdin();
@@ -0,0 +1,233 @@
+use std::str::FromStr;
+
+pub const FILLED: u8 = b'#';
+pub const EMPTY: u8 = b'.';
+pub const UNKNOWN: u8 = b'?';
+
+/// One line of cells, each `#`, `.` or `?`, and the lengths of the runs of
+/// `#` it must contain, in order.
+#[derive(Debug, Clone, Copy)]
+pub struct Line<'a> {
+    pub cells: &'a [u8],
+    pub runs: &'a [usize],
+}
+
+impl Line<'_> {
+    /// Where the next free position is after placing a run of `len` filled
+    /// cells starting at `i`, if it fits there. The run must be followed by
+    /// the end of the line or a cell that can be empty.
+    fn place(&self, i: usize, len: usize) -> Option<usize> {
+        let end = i + len;
+        if end > self.cells.len() || self.cells[i..end].contains(&EMPTY) {
+            return None;
+        }
+        match self.cells.get(end) {
+            None => Some(end),
+            Some(&FILLED) => None,
+            Some(_) => Some(end + 1),
+        }
+    }
+
+    /// `ways[i][j]`: in how many ways runs `j..` can be placed in cells
+    /// `i..`, given that cell `i - 1` was left empty.
+    fn suffixes(&self) -> Vec<Vec<usize>> {
+        let (n, m) = (self.cells.len(), self.runs.len());
+        let mut ways = vec![vec![0; m + 1]; n + 1];
+        ways[n][m] = 1;
+        for i in (0..n).rev() {
+            for j in 0..=m {
+                let mut w = 0;
+                if self.cells[i] != FILLED {
+                    w += ways[i + 1][j];
+                }
+                if let Some(next) = self.runs.get(j).and_then(|&r| self.place(i, r)) {
+                    w += ways[next][j + 1];
+                }
+                ways[i][j] = w;
+            }
+        }
+        ways
+    }
+
+    /// In how many ways the unknown cells can be filled in to match the runs.
+    pub fn count(&self) -> usize {
+        self.suffixes()[0][0]
+    }
+
+    /// Fills in every unknown cell that is the same in all arrangements.
+    /// Returns `None` if there are no arrangements at all.
+    pub fn deduce(&self) -> Option<Vec<u8>> {
+        let (n, m) = (self.cells.len(), self.runs.len());
+        let after = self.suffixes();
+        if after[0][0] == 0 {
+            return None;
+        }
+
+        // Walk forwards over the states that can both be reached from the
+        // start and still finish, noting what each cell can be on the way.
+        let mut reached = vec![vec![false; m + 1]; n + 1];
+        reached[0][0] = true;
+        let mut can_empty = vec![false; n];
+        let mut filled_from = vec![0isize; n + 1];
+        for i in 0..n {
+            for j in 0..=m {
+                if !reached[i][j] {
+                    continue;
+                }
+                if self.cells[i] != FILLED && after[i + 1][j] > 0 {
+                    reached[i + 1][j] = true;
+                    can_empty[i] = true;
+                }
+                let Some(&r) = self.runs.get(j) else {
+                    continue;
+                };
+                if let Some(next) = self.place(i, r).filter(|&next| after[next][j + 1] > 0) {
+                    reached[next][j + 1] = true;
+                    filled_from[i] += 1;
+                    filled_from[i + r] -= 1;
+                    if next > i + r {
+                        can_empty[i + r] = true;
+                    }
+                }
+            }
+        }
+
+        let mut filled = 0;
+        let mut out = self.cells.to_vec();
+        for (i, cell) in out.iter_mut().enumerate() {
+            filled += filled_from[i];
+            *cell = match (filled > 0, can_empty[i]) {
+                (true, false) => FILLED,
+                (false, true) => EMPTY,
+                _ => *cell,
+            };
+        }
+        Some(out)
+    }
+}
+
+/// A row of springs from the puzzle, like `???.### 1,1,3`.
+#[derive(Debug, Clone)]
+pub struct Row {
+    pub cells: Vec<u8>,
+    pub runs: Vec<usize>,
+}
+
+impl FromStr for Row {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (cells, runs) = s.trim().split_once(' ').ok_or(())?;
+        let cells = cells.bytes().collect::<Vec<_>>();
+        if cells.iter().any(|c| ![FILLED, EMPTY, UNKNOWN].contains(c)) {
+            return Err(());
+        }
+        let runs = runs
+            .split(',')
+            .map(|n| n.parse().map_err(|_| ()))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { cells, runs })
+    }
+}
+
+impl Row {
+    /// The row repeated `factor` times, with an unknown cell between each
+    /// copy of the cells.
+    pub fn unfold(&self, factor: usize) -> Row {
+        let mut cells = Vec::with_capacity((self.cells.len() + 1) * factor);
+        for i in 0..factor {
+            if i > 0 {
+                cells.push(UNKNOWN);
+            }
+            cells.extend_from_slice(&self.cells);
+        }
+        Row {
+            cells,
+            runs: self.runs.repeat(factor),
+        }
+    }
+
+    pub fn line(&self) -> Line<'_> {
+        Line {
+            cells: &self.cells,
+            runs: &self.runs,
+        }
+    }
+
+    pub fn arrangements(&self) -> usize {
+        self.line().count()
+    }
+}
+
+/// A picture puzzle: the runs for every row, top to bottom, and every
+/// column, left to right.
+#[derive(Debug, Clone)]
+pub struct Nonogram {
+    pub rows: Vec<Vec<usize>>,
+    pub columns: Vec<Vec<usize>>,
+}
+
+impl Nonogram {
+    /// Deduces each row and column in turn until nothing changes, guessing a
+    /// cell and backtracking if that isn't enough. Returns the first
+    /// solution found as one `#`/`.` row per line.
+    pub fn solve(&self) -> Option<Vec<Vec<u8>>> {
+        let grid = vec![vec![UNKNOWN; self.columns.len()]; self.rows.len()];
+        self.search(grid)
+    }
+
+    fn search(&self, mut grid: Vec<Vec<u8>>) -> Option<Vec<Vec<u8>>> {
+        self.propagate(&mut grid)?;
+
+        let Some((y, x)) = (0..grid.len())
+            .flat_map(|y| (0..self.columns.len()).map(move |x| (y, x)))
+            .find(|&(y, x)| grid[y][x] == UNKNOWN)
+        else {
+            return Some(grid);
+        };
+
+        [FILLED, EMPTY].into_iter().find_map(|guess| {
+            let mut next = grid.clone();
+            next[y][x] = guess;
+            self.search(next)
+        })
+    }
+
+    /// Applies line deduction until it stops making progress. Fails if any
+    /// line can't be completed.
+    fn propagate(&self, grid: &mut [Vec<u8>]) -> Option<()> {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (y, runs) in self.rows.iter().enumerate() {
+                let cells = &grid[y];
+                let deduced = Line { cells, runs }.deduce()?;
+                if deduced != *cells {
+                    grid[y] = deduced;
+                    changed = true;
+                }
+            }
+            for (x, runs) in self.columns.iter().enumerate() {
+                let cells: Vec<u8> = grid.iter().map(|row| row[x]).collect();
+                let deduced = Line {
+                    cells: &cells,
+                    runs,
+                }
+                .deduce()?;
+                if deduced != cells {
+                    for (row, c) in grid.iter_mut().zip(deduced) {
+                        row[x] = c;
+                    }
+                    changed = true;
+                }
+            }
+        }
+        Some(())
+    }
+}
+
+pub fn render(grid: &[Vec<u8>]) -> String {
+    grid.iter()
+        .map(|row| String::from_utf8_lossy(row).into_owned() + "\n")
+        .collect()
+}
@@ -0,0 +1,401 @@
+use rustc_hash::FxHashMap;
+use std::{fmt::Write, str::FromStr};
+
+/// The workflow every part starts in.
+pub const START: &str = "in";
+
+pub type NodeId = usize;
+
+const ACCEPT: NodeId = 0;
+const REJECT: NodeId = 1;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Cmp {
+    Lt,
+    Gt,
+}
+
+impl Cmp {
+    fn holds(self, rating: isize, value: isize) -> bool {
+        match self {
+            Cmp::Lt => rating < value,
+            Cmp::Gt => rating > value,
+        }
+    }
+
+    /// Splits an inclusive range into the part where the comparison holds
+    /// and the part where it doesn't. Either may be empty.
+    fn split(self, (lo, hi): (isize, isize), value: isize) -> ((isize, isize), (isize, isize)) {
+        match self {
+            Cmp::Lt => ((lo, hi.min(value - 1)), (lo.max(value), hi)),
+            Cmp::Gt => ((lo.max(value + 1), hi), (lo, hi.min(value))),
+        }
+    }
+
+    fn symbol(self) -> char {
+        match self {
+            Cmp::Lt => '<',
+            Cmp::Gt => '>',
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Node {
+    Accept,
+    Reject,
+    /// Continues at `then` if the attribute compares true against `value`,
+    /// and at `otherwise` if not.
+    Test {
+        attribute: usize,
+        cmp: Cmp,
+        value: isize,
+        then: NodeId,
+        otherwise: NodeId,
+        /// The workflow this test came from.
+        workflow: usize,
+    },
+}
+
+#[derive(Debug)]
+pub enum WorkflowError {
+    BadLine(String),
+    UnknownWorkflow(String),
+    /// Workflows that send parts round in a loop, in the order they're
+    /// visited.
+    Cycle(Vec<String>),
+    NoStart,
+}
+
+/// Where a rule sends a part, before workflow names are resolved.
+#[derive(Debug, Clone)]
+enum Target {
+    Accept,
+    Reject,
+    Workflow(String),
+}
+
+impl From<&str> for Target {
+    fn from(s: &str) -> Self {
+        match s {
+            "A" => Target::Accept,
+            "R" => Target::Reject,
+            _ => Target::Workflow(s.into()),
+        }
+    }
+}
+
+/// One workflow's rules, as `(attribute, cmp, value, target)` and the
+/// target for parts that match none of them.
+#[derive(Debug, Clone)]
+struct Rules {
+    tests: Vec<(usize, Cmp, isize, Target)>,
+    fallback: Target,
+}
+
+/// Every workflow compiled into one decision graph, with each workflow's
+/// rules turned into a chain of tests and each jump to another workflow
+/// pointing straight at that workflow's first test. Workflows no part can
+/// reach are compiled as well, so a loop anywhere is an error.
+#[derive(Debug, Clone)]
+pub struct Decisions {
+    pub attributes: Vec<String>,
+    pub workflows: Vec<String>,
+    pub nodes: Vec<Node>,
+    pub root: NodeId,
+    /// Workflows that no part can ever reach from the start.
+    pub unreachable: Vec<String>,
+}
+
+impl FromStr for Decisions {
+    type Err = WorkflowError;
+
+    /// Reads the workflow section of the puzzle input, stopping at the first
+    /// blank line.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut attributes: Vec<String> = vec![];
+        let mut workflows: Vec<String> = vec![];
+        let mut rules: FxHashMap<String, Rules> = FxHashMap::default();
+
+        for l in input.lines().map(str::trim).take_while(|l| !l.is_empty()) {
+            let bad = || WorkflowError::BadLine(l.into());
+            let (name, body) = l
+                .strip_suffix('}')
+                .and_then(|l| l.split_once('{'))
+                .ok_or_else(bad)?;
+            let mut parts: Vec<&str> = body.split(',').collect();
+            let fallback = parts.pop().filter(|f| !f.contains(':')).ok_or_else(bad)?;
+
+            let mut tests = vec![];
+            for rule in parts {
+                let (guard, target) = rule.split_once(':').ok_or_else(bad)?;
+                let at = guard.find(['<', '>']).ok_or_else(bad)?;
+                let (attribute, value) = (&guard[..at], &guard[at + 1..]);
+                let cmp = if guard[at..].starts_with('<') {
+                    Cmp::Lt
+                } else {
+                    Cmp::Gt
+                };
+                let value = value.parse().map_err(|_| bad())?;
+                let attribute = match attributes.iter().position(|a| a == attribute) {
+                    Some(i) => i,
+                    None => {
+                        attributes.push(attribute.into());
+                        attributes.len() - 1
+                    }
+                };
+                tests.push((attribute, cmp, value, target.into()));
+            }
+
+            workflows.push(name.into());
+            rules.insert(
+                name.into(),
+                Rules {
+                    tests,
+                    fallback: fallback.into(),
+                },
+            );
+        }
+
+        let mut compiler = Compiler {
+            workflows: &workflows,
+            rules: &rules,
+            nodes: vec![Node::Accept, Node::Reject],
+            roots: FxHashMap::default(),
+            path: vec![],
+        };
+        if !rules.contains_key(START) {
+            return Err(WorkflowError::NoStart);
+        }
+        let root = compiler.workflow(START)?;
+        let unreachable = workflows
+            .iter()
+            .filter(|w| !compiler.roots.contains_key(*w))
+            .cloned()
+            .collect();
+        // Compile the rest too, so loops and unknown targets are caught
+        // even where no part can get to them.
+        for w in &workflows {
+            compiler.workflow(w)?;
+        }
+        let nodes = compiler.nodes;
+
+        Ok(Decisions {
+            unreachable,
+            attributes,
+            workflows,
+            nodes,
+            root,
+        })
+    }
+}
+
+struct Compiler<'a> {
+    workflows: &'a [String],
+    rules: &'a FxHashMap<String, Rules>,
+    nodes: Vec<Node>,
+    roots: FxHashMap<String, NodeId>,
+    /// The workflows currently being compiled, outermost first.
+    path: Vec<String>,
+}
+
+impl Compiler<'_> {
+    fn workflow(&mut self, name: &str) -> Result<NodeId, WorkflowError> {
+        if let Some(&id) = self.roots.get(name) {
+            return Ok(id);
+        }
+        if let Some(at) = self.path.iter().position(|w| w == name) {
+            return Err(WorkflowError::Cycle(self.path[at..].to_vec()));
+        }
+        let rules = self
+            .rules
+            .get(name)
+            .ok_or_else(|| WorkflowError::UnknownWorkflow(name.into()))?;
+        let index = self.workflows.iter().position(|w| w == name).unwrap();
+
+        self.path.push(name.into());
+        let mut next = self.target(&rules.fallback)?;
+        for (attribute, cmp, value, target) in rules.tests.iter().rev() {
+            let then = self.target(target)?;
+            self.nodes.push(Node::Test {
+                attribute: *attribute,
+                cmp: *cmp,
+                value: *value,
+                then,
+                otherwise: next,
+                workflow: index,
+            });
+            next = self.nodes.len() - 1;
+        }
+        self.path.pop();
+
+        self.roots.insert(name.into(), next);
+        Ok(next)
+    }
+
+    fn target(&mut self, target: &Target) -> Result<NodeId, WorkflowError> {
+        match target {
+            Target::Accept => Ok(ACCEPT),
+            Target::Reject => Ok(REJECT),
+            Target::Workflow(name) => self.workflow(name),
+        }
+    }
+}
+
+/// Reads a part like `{x=787,m=2655,a=1222,s=2876}`.
+pub fn parse_part(s: &str) -> Option<Vec<(String, isize)>> {
+    s.trim()
+        .strip_prefix('{')?
+        .strip_suffix('}')?
+        .split(',')
+        .map(|r| {
+            let (name, value) = r.split_once('=')?;
+            Some((name.into(), value.parse().ok()?))
+        })
+        .collect()
+}
+
+impl Decisions {
+    fn attribute(&self, name: &str) -> Option<usize> {
+        self.attributes.iter().position(|a| a == name)
+    }
+
+    /// Whether a part ends up accepted. `None` if the part is missing a
+    /// rating that one of the workflows on its way looks at.
+    pub fn accepts(&self, part: &[(String, isize)]) -> Option<bool> {
+        let mut ratings = vec![None; self.attributes.len()];
+        for (name, value) in part {
+            if let Some(i) = self.attribute(name) {
+                ratings[i] = Some(*value);
+            }
+        }
+
+        let mut at = self.root;
+        loop {
+            match self.nodes[at] {
+                Node::Accept => return Some(true),
+                Node::Reject => return Some(false),
+                Node::Test {
+                    attribute,
+                    cmp,
+                    value,
+                    then,
+                    otherwise,
+                    ..
+                } => {
+                    at = if cmp.holds(ratings[attribute]?, value) {
+                        then
+                    } else {
+                        otherwise
+                    }
+                }
+            }
+        }
+    }
+
+    /// How many combinations of ratings within `bounds` are accepted. Each
+    /// bound is an attribute and its inclusive range; attributes no workflow
+    /// looks at only multiply the count. `None` if an attribute that is
+    /// looked at isn't bounded.
+    pub fn accepted_volume(&self, bounds: &[(&str, isize, isize)]) -> Option<u128> {
+        let mut ranges = vec![None; self.attributes.len()];
+        let mut free = 1;
+        for &(name, lo, hi) in bounds {
+            match self.attribute(name) {
+                Some(i) => ranges[i] = Some((lo, hi)),
+                None => free *= size((lo, hi)),
+            }
+        }
+        let mut ranges = ranges.into_iter().collect::<Option<Vec<_>>>()?;
+        Some(free * self.volume(self.root, &mut ranges))
+    }
+
+    fn volume(&self, at: NodeId, ranges: &mut [(isize, isize)]) -> u128 {
+        match self.nodes[at] {
+            Node::Accept => ranges.iter().map(|&r| size(r)).product(),
+            Node::Reject => 0,
+            Node::Test {
+                attribute,
+                cmp,
+                value,
+                then,
+                otherwise,
+                ..
+            } => {
+                let range = ranges[attribute];
+                let (pass, fail) = cmp.split(range, value);
+                let mut total = 0;
+                for (r, next) in [(pass, then), (fail, otherwise)] {
+                    if r.0 <= r.1 {
+                        ranges[attribute] = r;
+                        total += self.volume(next, ranges);
+                    }
+                }
+                ranges[attribute] = range;
+                total
+            }
+        }
+    }
+
+    /// The decision graph in Graphviz DOT format, with each workflow's tests
+    /// grouped in a box.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph workflows {\n");
+        out.push_str("  n0 [label=\"A\", shape=doublecircle];\n");
+        out.push_str("  n1 [label=\"R\", shape=circle];\n");
+
+        let mut clusters: Vec<Vec<NodeId>> = vec![vec![]; self.workflows.len()];
+        for (id, node) in self.nodes.iter().enumerate() {
+            if let Node::Test { workflow, .. } = node {
+                clusters[*workflow].push(id);
+            }
+        }
+        for (w, ids) in clusters
+            .iter()
+            .enumerate()
+            .filter(|(_, ids)| !ids.is_empty())
+        {
+            let _ = writeln!(out, "  subgraph cluster_{} {{", w);
+            let _ = writeln!(out, "    label=\"{}\";", self.workflows[w]);
+            for &id in ids {
+                if let Node::Test {
+                    attribute,
+                    cmp,
+                    value,
+                    ..
+                } = self.nodes[id]
+                {
+                    let _ = writeln!(
+                        out,
+                        "    n{} [label=\"{}{}{}\", shape=diamond];",
+                        id,
+                        self.attributes[attribute],
+                        cmp.symbol(),
+                        value
+                    );
+                }
+            }
+            out.push_str("  }\n");
+        }
+
+        for (id, node) in self.nodes.iter().enumerate() {
+            if let Node::Test {
+                then, otherwise, ..
+            } = node
+            {
+                let _ = writeln!(out, "  n{} -> n{} [label=\"yes\"];", id, then);
+                let _ = writeln!(out, "  n{} -> n{} [label=\"no\"];", id, otherwise);
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+fn size((lo, hi): (isize, isize)) -> u128 {
+    if lo > hi {
+        0
+    } else {
+        (hi - lo + 1) as u128
+    }
+}